@@ -0,0 +1,186 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fmt::Write,
+    fs::{self, read_to_string},
+    path::PathBuf,
+};
+
+use crate::{
+    input,
+    solution::{Answer, Part},
+};
+
+const PENDING: &str = "pending";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expected {
+    Accepted(String),
+    Pending,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    Correct,
+    Mismatch { expected: String },
+    Pending,
+}
+
+// Accepted answers for the real inputs of one year, stored as
+//
+//     [day1]
+//     part1 = 55029
+//     part2 = "pending"
+pub struct AnswerStore {
+    path: PathBuf,
+    answers: BTreeMap<(u32, Part), Expected>,
+}
+
+impl AnswerStore {
+    pub fn for_year(year: u32) -> Result<Self, Box<dyn Error>> {
+        AnswerStore::load(input::manifest_dir()?.join(format!("answers/{year}.toml")))
+    }
+
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, Box<dyn Error>> {
+        let path = path.into();
+        let mut answers = BTreeMap::new();
+        if path.exists() {
+            let table = read_to_string(&path)?.parse::<toml::Table>()?;
+            for (day_key, parts) in table {
+                let day = day_key
+                    .strip_prefix("day")
+                    .and_then(|day| day.parse::<u32>().ok())
+                    .ok_or(format!("unexpected section [{day_key}]"))?;
+                let parts = parts
+                    .as_table()
+                    .ok_or(format!("[{day_key}] is not a table"))?;
+                for (part_key, value) in parts {
+                    let part = part_key
+                        .strip_prefix("part")
+                        .ok_or(format!("unexpected key {part_key} in [{day_key}]"))?
+                        .parse::<Part>()?;
+                    let expected = match value {
+                        toml::Value::String(s) if s == PENDING => Expected::Pending,
+                        toml::Value::String(s) => Expected::Accepted(s.clone()),
+                        toml::Value::Integer(i) => Expected::Accepted(i.to_string()),
+                        _ => {
+                            return Err(format!("unexpected answer for {day_key}.{part_key}").into())
+                        }
+                    };
+                    answers.insert((day, part), expected);
+                }
+            }
+        }
+        Ok(AnswerStore { path, answers })
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn Error>> {
+        let mut out = String::new();
+        let mut current_day = None;
+        for ((day, part), expected) in &self.answers {
+            if current_day != Some(*day) {
+                if current_day.is_some() {
+                    out.push('\n');
+                }
+                writeln!(out, "[day{day}]")?;
+                current_day = Some(*day);
+            }
+            match expected {
+                Expected::Accepted(answer) if answer.parse::<i64>().is_ok() => {
+                    writeln!(out, "part{part} = {answer}")?
+                }
+                Expected::Accepted(answer) => writeln!(out, "part{part} = {answer:?}")?,
+                Expected::Pending => writeln!(out, "part{part} = {PENDING:?}")?,
+            }
+        }
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.path, out)?;
+        Ok(())
+    }
+
+    pub fn get(&self, day: u32, part: Part) -> Option<&Expected> {
+        self.answers.get(&(day, part))
+    }
+
+    pub fn accept(&mut self, day: u32, part: Part, answer: &Answer) {
+        self.answers
+            .insert((day, part), Expected::Accepted(answer.to_string()));
+    }
+
+    // Answers that were never accepted are recorded as pending, they neither
+    // pass nor fail
+    pub fn check(&mut self, day: u32, part: Part, answer: &Answer) -> Check {
+        match self.answers.entry((day, part)).or_insert(Expected::Pending) {
+            Expected::Accepted(expected) if *expected == answer.to_string() => Check::Correct,
+            Expected::Accepted(expected) => Check::Mismatch {
+                expected: expected.clone(),
+            },
+            Expected::Pending => Check::Pending,
+        }
+    }
+}
+
+// Used by the real input tests so a changed answer fails the test run. A
+// part without an accepted answer fails too, otherwise its test could never
+// catch anything.
+#[cfg(test)]
+pub fn assert_answer(year: u32, day: u32, part: Part, answer: &Answer) {
+    let store = AnswerStore::for_year(year).unwrap();
+    match store.get(day, part) {
+        Some(Expected::Accepted(expected)) => assert_eq!(
+            answer.to_string(),
+            *expected,
+            "day {day} part {part} does not match the accepted answer"
+        ),
+        _ => panic!("day {day} part {part} has no accepted answer yet, got {answer}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::mock::temp_dir;
+
+    use super::*;
+
+    #[test]
+    fn check_and_save_answers() {
+        let dir = temp_dir("answers");
+        let path = dir.join("2023.toml");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(&path, "[day1]\npart1 = 142\npart2 = \"pending\"\n").unwrap();
+
+        let mut store = AnswerStore::load(&path).unwrap();
+        assert_eq!(
+            store.check(1, Part::One, &Answer::from(142)),
+            Check::Correct
+        );
+        assert_eq!(
+            store.check(1, Part::One, &Answer::from(143)),
+            Check::Mismatch {
+                expected: "142".to_owned()
+            }
+        );
+        assert_eq!(
+            store.check(1, Part::Two, &Answer::from(281)),
+            Check::Pending
+        );
+        assert_eq!(store.check(10, Part::One, &Answer::from(8)), Check::Pending);
+        store.accept(2, Part::Two, &Answer::from("abc"));
+        store.save().unwrap();
+
+        assert_eq!(
+            read_to_string(&path).unwrap(),
+            "[day1]\npart1 = 142\npart2 = \"pending\"\n\n\
+             [day2]\npart2 = \"abc\"\n\n\
+             [day10]\npart1 = \"pending\"\n"
+        );
+        let store = AnswerStore::load(&path).unwrap();
+        assert_eq!(
+            store.get(2, Part::Two),
+            Some(&Expected::Accepted("abc".to_owned()))
+        );
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    fmt::Write,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    error::AocResult,
+    solution::{Day, Part},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        assert!(!samples.is_empty(), "no samples to summarize");
+        samples.sort();
+        Stats {
+            min: samples[0],
+            median: samples[samples.len() / 2],
+            max: samples[samples.len() - 1],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DayTimings {
+    pub day: u32,
+    pub parse: Stats,
+    pub part1: Stats,
+    pub part2: Stats,
+}
+
+impl DayTimings {
+    pub fn phases(&self) -> [(&'static str, Stats); 3] {
+        [
+            ("parse", self.parse),
+            ("part1", self.part1),
+            ("part2", self.part2),
+        ]
+    }
+}
+
+// Every iteration parses the input again and runs both parts on the result,
+// with each phase timed on its own
+pub fn bench_day(day: &Day, input: &str, iterations: usize) -> AocResult<DayTimings> {
+    let params = day.solution.params();
+    let mut parse = Vec::with_capacity(iterations);
+    let mut part1 = Vec::with_capacity(iterations);
+    let mut part2 = Vec::with_capacity(iterations);
+    for _ in 0..iterations {
+        let start = Instant::now();
+        let parsed = day.solution.parse_input(input)?;
+        parse.push(start.elapsed());
+
+        for (part, samples) in [(Part::One, &mut part1), (Part::Two, &mut part2)] {
+            let start = Instant::now();
+            let answer = day.solution.solve(part, parsed.as_ref(), &params);
+            samples.push(start.elapsed());
+            std::hint::black_box(answer);
+        }
+    }
+    Ok(DayTimings {
+        day: day.day,
+        parse: Stats::from_samples(parse),
+        part1: Stats::from_samples(part1),
+        part2: Stats::from_samples(part2),
+    })
+}
+
+// One phase per line with times in nanoseconds, so two reports diff cleanly
+pub fn to_json(iterations: usize, timings: &[DayTimings]) -> String {
+    let mut out = String::new();
+    writeln!(out, "{{").unwrap();
+    writeln!(out, "  \"iterations\": {iterations},").unwrap();
+    writeln!(out, "  \"days\": [").unwrap();
+    for (i, day) in timings.iter().enumerate() {
+        writeln!(out, "    {{").unwrap();
+        writeln!(out, "      \"day\": {},", day.day).unwrap();
+        for (j, (name, stats)) in day.phases().iter().enumerate() {
+            let comma = if j < 2 { "," } else { "" };
+            writeln!(
+                out,
+                "      \"{name}\": {{ \"min_ns\": {}, \"median_ns\": {}, \"max_ns\": {} }}{comma}",
+                stats.min.as_nanos(),
+                stats.median.as_nanos(),
+                stats.max.as_nanos()
+            )
+            .unwrap();
+        }
+        let comma = if i + 1 < timings.len() { "," } else { "" };
+        writeln!(out, "    }}{comma}").unwrap();
+    }
+    writeln!(out, "  ]").unwrap();
+    writeln!(out, "}}").unwrap();
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::solution::find_day;
+
+    use super::*;
+
+    #[test]
+    fn stats_from_samples() {
+        let samples = [5, 1, 4, 2, 3].map(Duration::from_millis).to_vec();
+        let stats = Stats::from_samples(samples);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.max, Duration::from_millis(5));
+    }
+
+    #[test]
+    fn bench_report() {
+        let example = "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet";
+        let timings = bench_day(find_day(1).unwrap(), example, 3).unwrap();
+        assert_eq!(timings.day, 1);
+        assert!(timings.parse.min <= timings.parse.median);
+        assert!(timings.part2.median <= timings.part2.max);
+
+        let json = to_json(3, &[timings]);
+        assert!(
+            json.starts_with("{\n  \"iterations\": 3,\n  \"days\": [\n    {\n      \"day\": 1,\n")
+        );
+        assert_eq!(json.matches("\"median_ns\"").count(), 3);
+        assert!(json.ends_with("    }\n  ]\n}\n"));
+    }
+}
@@ -1,8 +1,16 @@
-fn solve_part1(input: &str) -> i32 {
-    input
-        .lines()
+use crate::error::AocResult;
+use crate::solution::{Answer, Params, Solution};
+
+pub struct Day1;
+
+fn calibration_values(lines: &[String]) -> i32 {
+    lines
+        .iter()
         .map(|line| {
-            let digits = line.chars().filter(|c| c.is_digit(10)).collect::<Vec<_>>();
+            let digits = line
+                .chars()
+                .filter(|c| c.is_ascii_digit())
+                .collect::<Vec<_>>();
             if let (Some(first), Some(last)) = (digits.first(), digits.last()) {
                 let first = (*first as usize - 48) as i32 * 10;
                 let last = (*last as usize - 48) as i32;
@@ -53,45 +61,60 @@ fn find_digits_in_line(input: &str) -> i32 {
     }
 
     if let (Some(first), Some(last)) = (digits.first(), digits.last()) {
-        let res = first * 10 + last;
-        res
+        first * 10 + last
     } else {
         0
     }
 }
 
-fn solve_part2(input: &str) -> i32 {
-    input.lines().map(find_digits_in_line).sum()
+impl Solution for Day1 {
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> AocResult<Self::Input> {
+        Ok(input.lines().map(str::to_owned).collect())
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Answer {
+        calibration_values(input).into()
+    }
+
+    fn part2(input: &Self::Input, _params: &Params) -> Answer {
+        input
+            .iter()
+            .map(|line| find_digits_in_line(line))
+            .sum::<i32>()
+            .into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::get_input;
+    use crate::{answers::assert_answer, get_input, solution::Part};
 
     #[test]
     fn day1_part1_test() {
         let test_input = "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet";
-        let res = solve_part1(test_input);
+        let res = Day1::solve_part1(test_input).unwrap();
         assert_eq!(res, 142);
     }
     #[test]
     fn day1_part1() {
         let input = get_input(2023, 1).unwrap();
-        let res = solve_part1(&input);
-        println!("Day1 Part1 Result: {res}");
+        let res = Day1::solve_part1(&input).unwrap();
+        assert_answer(2023, 1, Part::One, &res);
     }
     #[test]
     fn day1_part2_test() {
         let test_input = "two1nine\neightwothree\nabcone2threexyz\nxtwone3four\n4nineeightseven2\nzoneight234\n7pqrstsixteen";
-        let res = solve_part2(test_input);
+        let res = Day1::solve_part2(test_input).unwrap();
         assert_eq!(res, 281);
     }
 
     #[test]
     fn day1_part2() {
         let test_input = get_input(2023, 1).unwrap();
-        let res = solve_part2(&test_input);
-        println!("Day1 Part2 Result: {res}");
+        let res = Day1::solve_part2(&test_input).unwrap();
+        assert_answer(2023, 1, Part::Two, &res);
     }
 }
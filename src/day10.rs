@@ -8,6 +8,12 @@ use std::{
     slice::SliceIndex,
     str::FromStr,
 };
+
+use crate::error::{AocError, AocResult};
+use crate::geometry::{Direction, Point};
+use crate::grid::Grid;
+use crate::search;
+use crate::solution::{Answer, Params, Solution};
 const EXAMPLE: &str = r#"-L|F7
 7S-7|
 L|7||
@@ -62,126 +68,63 @@ const EXAMPLE_PART2_SMALLEST: &str = r#"...........
 .L--J.L--J.
 ..........."#;
 
-struct Field {
-    field: Vec<Vec<u8>>,
-    height: usize,
-    width: usize,
-}
+type Field = Grid<u8>;
 
-impl Field {
-    fn new(field: Vec<Vec<u8>>) -> Self {
-        let height = field.len();
-        let width = field[0].len();
-        Self {
-            field,
-            height,
-            width,
-        }
-    }
-    fn index(&self, pos: Pos) -> Option<u8> {
-        if pos.0 < 0 || pos.1 < 0 || pos.0 as usize >= self.width || pos.1 as usize >= self.height {
-            return None;
-        }
-        Some(self.field[pos.1 as usize][pos.0 as usize])
-    }
-}
-impl Debug for Field {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for l in &self.field {
-            for c in l {
-                f.write_char(*c as char)?;
-            }
-            f.write_char('\n')?;
-        }
-        Ok(())
-    }
-}
+type Pos = Point<i32>;
 
-type Pos = (i32, i32);
-
-fn parse(input: &str) -> (Pos, Field) {
-    let mut start_pos = None;
-    let field = input
-        .lines()
-        .enumerate()
-        .map(|(row, l)| {
-            if start_pos.is_none() {
-                if let Some(col) = l.find('S') {
-                    start_pos = Some((col as i32, row as i32));
-                }
-            }
-            l.as_bytes().iter().copied().collect::<Vec<u8>>()
-        })
-        .collect();
-    (start_pos.unwrap(), Field::new(field))
+// The loop walks off the field with negative positions
+fn pipe_at(field: &Field, pos: Pos) -> Option<u8> {
+    let pos = Point::new(usize::try_from(pos.x).ok()?, usize::try_from(pos.y).ok()?);
+    field.get(pos).copied()
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
-enum Dir {
-    #[default]
-    Left,
-    Up,
-    Right,
-    Down,
+fn parse(input: &str) -> AocResult<(Pos, Field)> {
+    let field = Grid::parse(input, b"|-LJ7F.S")?;
+    let start = field
+        .find(|&pipe| pipe == b'S')
+        .ok_or("missing start 'S'")?;
+    Ok((Point::new(start.x as i32, start.y as i32), field))
 }
 
-fn next_pos(pos: Pos, dir: Dir, pipe: u8) -> Option<(Pos, Dir)> {
-    let next = match pipe {
-        b'|' => match dir {
-            Dir::Up => ((pos.0, pos.1 - 1), dir),
-            Dir::Down => ((pos.0, pos.1 + 1), dir),
-            _ => return None,
-        },
-        b'-' => match dir {
-            Dir::Left => ((pos.0 - 1, pos.1), dir),
-            Dir::Right => ((pos.0 + 1, pos.1), dir),
-            _ => return None,
-        },
-        b'L' => match dir {
-            Dir::Down => ((pos.0 + 1, pos.1), Dir::Right),
-            Dir::Left => ((pos.0, pos.1 - 1), Dir::Up),
-            _ => return None,
-        },
-        b'J' => match dir {
-            Dir::Down => ((pos.0 - 1, pos.1), Dir::Left),
-            Dir::Right => ((pos.0, pos.1 - 1), Dir::Up),
-            _ => return None,
-        },
-        b'7' => match dir {
-            Dir::Up => ((pos.0 - 1, pos.1), Dir::Left),
-            Dir::Right => ((pos.0, pos.1 + 1), Dir::Down),
-            _ => return None,
-        },
-        b'F' => match dir {
-            Dir::Up => ((pos.0 + 1, pos.1), Dir::Right),
-            Dir::Left => ((pos.0, pos.1 + 1), Dir::Down),
-            _ => return None,
-        },
+fn next_pos(pos: Pos, dir: Direction, pipe: u8) -> Option<(Pos, Direction)> {
+    let next_dir = match (pipe, dir) {
+        (b'|', Direction::Up | Direction::Down) | (b'-', Direction::Left | Direction::Right) => dir,
+        (b'L', Direction::Down) | (b'F', Direction::Up) => Direction::Right,
+        (b'L', Direction::Left) | (b'J', Direction::Right) => Direction::Up,
+        (b'J', Direction::Down) | (b'7', Direction::Up) => Direction::Left,
+        (b'7', Direction::Right) | (b'F', Direction::Left) => Direction::Down,
         _ => return None,
     };
-    Some(next)
+    Some((pos.step(next_dir)?, next_dir))
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 struct Loop {
     length: u32,
-    start_dir: Dir,
-    end_dir: Dir,
-    pipes: Vec<(i32, i32)>,
+    start_dir: Direction,
+    end_dir: Direction,
+    pipes: Vec<Pos>,
 }
 
 fn traverse(start_pos: Pos, field: &Field) -> Loop {
-    let mut current_loop = Loop::default();
+    let mut current_loop = Loop {
+        length: 0,
+        start_dir: Direction::Left,
+        end_dir: Direction::Left,
+        pipes: Vec::new(),
+    };
 
-    for (d_pos, start_dir) in [
-        ((-1, 0), Dir::Left),
-        ((1, 0), Dir::Right),
-        ((0, -1), Dir::Up),
-        ((0, 1), Dir::Down),
+    for start_dir in [
+        Direction::Left,
+        Direction::Right,
+        Direction::Up,
+        Direction::Down,
     ] {
-        let mut pos = (start_pos.0 + d_pos.0, start_pos.1 + d_pos.1);
+        let Some(mut pos) = start_pos.step(start_dir) else {
+            continue;
+        };
 
-        if let Some(mut pipe) = field.index(pos) {
+        if let Some(mut pipe) = pipe_at(field, pos) {
             let mut dir = start_dir;
             let mut steps = 1;
             let mut pipes = vec![start_pos, pos];
@@ -189,12 +132,11 @@ fn traverse(start_pos: Pos, field: &Field) -> Loop {
                 pos = next_pos;
                 dir = next_dir;
 
-                if let Some(next_pipe) = field.index(pos) {
+                if let Some(next_pipe) = pipe_at(field, pos) {
                     steps += 1;
                     pipes.push(pos);
                     pipe = next_pipe;
                     if pipe == b'S' {
-                        println!("Found a loop with length {steps} ");
                         break;
                     }
                 } else {
@@ -215,29 +157,21 @@ fn traverse(start_pos: Pos, field: &Field) -> Loop {
 fn substitute_start_pipe(looop: &Loop, field: &mut Field) {
     let start_pos = looop.pipes[0];
     let start_char = match (looop.start_dir, looop.end_dir) {
-        (Dir::Down, Dir::Down) => b'|',
-        (Dir::Up, Dir::Up) => b'|',
-        (Dir::Left, Dir::Left) => b'-',
-        (Dir::Right, Dir::Right) => b'-',
-        (Dir::Down, Dir::Left) => b'F',
-        (Dir::Down, Dir::Right) => b'7',
-        (Dir::Up, Dir::Left) => b'L',
-        (Dir::Up, Dir::Right) => b'J',
-        (Dir::Left, Dir::Down) => b'J',
-        (Dir::Left, Dir::Up) => b'7',
-        (Dir::Right, Dir::Down) => b'L',
-        (Dir::Right, Dir::Up) => b'F',
+        (Direction::Down, Direction::Down) => b'|',
+        (Direction::Up, Direction::Up) => b'|',
+        (Direction::Left, Direction::Left) => b'-',
+        (Direction::Right, Direction::Right) => b'-',
+        (Direction::Down, Direction::Left) => b'F',
+        (Direction::Down, Direction::Right) => b'7',
+        (Direction::Up, Direction::Left) => b'L',
+        (Direction::Up, Direction::Right) => b'J',
+        (Direction::Left, Direction::Down) => b'J',
+        (Direction::Left, Direction::Up) => b'7',
+        (Direction::Right, Direction::Down) => b'L',
+        (Direction::Right, Direction::Up) => b'F',
         _ => unreachable!(),
     };
-    println!("Replacing startpos with {}", start_char as char);
-    field.field[start_pos.1 as usize][start_pos.0 as usize] = start_char;
-}
-
-fn solve_part1(input: &str) -> u32 {
-    let (start_pos, mut field) = parse(input);
-    let found_loop = traverse(start_pos, &mut field);
-
-    found_loop.length / 2
+    field[Point::new(start_pos.x as usize, start_pos.y as usize)] = start_char;
 }
 
 fn find_pools(looop: &Loop, field: &Field) -> u32 {
@@ -247,9 +181,9 @@ fn find_pools(looop: &Loop, field: &Field) -> u32 {
     let mut visited = HashSet::new();
     let mut stack = Vec::new();
 
-    for height in 0..field.height {
-        for width in 0..field.width {
-            let start_pos = (width as i32, height as i32);
+    for height in 0..field.height() {
+        for width in 0..field.width() {
+            let start_pos = Point::new(width as i32, height as i32);
             if visited.contains(&start_pos) || loop_coords.contains(&start_pos) {
                 continue;
             };
@@ -267,11 +201,13 @@ fn find_pools(looop: &Loop, field: &Field) -> u32 {
                             continue;
                         }
 
-                        let new_pos = (pos.0 + dx, pos.1 + dy);
-                        if field.index(new_pos).is_none() {
+                        let Some(new_pos) = pos
+                            .offset(dx, dy)
+                            .filter(|&new_pos| pipe_at(field, new_pos).is_some())
+                        else {
                             touches_outside = true;
                             continue;
-                        }
+                        };
 
                         if loop_coords.contains(&new_pos) {
                             loop_neighbours.push(new_pos);
@@ -302,7 +238,7 @@ fn find_pools(looop: &Loop, field: &Field) -> u32 {
         .flatten()
         .collect::<HashSet<_>>();
 
-    let maybe_inside_pools: Vec<Vec<((i32, i32), Vec<(i32, i32)>)>> = pools
+    let maybe_inside_pools: Vec<Pool> = pools
         .iter()
         .filter_map(|pool| if !pool.0 { Some(pool.1.clone()) } else { None })
         .collect::<Vec<_>>();
@@ -318,7 +254,11 @@ fn find_pools(looop: &Loop, field: &Field) -> u32 {
         .sum()
 }
 
-const EDGE_POINT_OFFSETS: [(i32, i32); 9] = [
+// A tile in a pool together with the loop pipes it borders
+type PoolTile = (Pos, Vec<Pos>);
+type Pool = Vec<PoolTile>;
+
+const EDGE_POINT_OFFSETS: [(isize, isize); 9] = [
     (-1, -1),
     (0, -1),
     (1, -1),
@@ -330,28 +270,32 @@ const EDGE_POINT_OFFSETS: [(i32, i32); 9] = [
     (-1, -1),
 ];
 
-const DIRECTION: [Dir; 8] = [
-    Dir::Up,
-    Dir::Up,
-    Dir::Right,
-    Dir::Right,
-    Dir::Down,
-    Dir::Down,
-    Dir::Left,
-    Dir::Left,
+const DIRECTION: [Direction; 8] = [
+    Direction::Up,
+    Direction::Up,
+    Direction::Right,
+    Direction::Right,
+    Direction::Down,
+    Direction::Down,
+    Direction::Left,
+    Direction::Left,
 ];
 
 fn is_inside(
-    outside_nodes: &HashSet<(i32, i32)>,
-    loop_coords: &HashSet<(i32, i32)>,
+    outside_nodes: &HashSet<Pos>,
+    loop_coords: &HashSet<Pos>,
     field: &Field,
-    pool: &Vec<((i32, i32), Vec<(i32, i32)>)>,
+    pool: &[PoolTile],
 ) -> bool {
     let mut start_points = Vec::new();
     for (point, _) in pool.iter().filter(|p| !p.1.is_empty()) {
         for (i, window) in EDGE_POINT_OFFSETS.windows(2).enumerate() {
-            let pos1 = (point.0 + window[0].0, point.1 + window[0].1);
-            let pos2 = (point.0 + window[1].0, point.1 + window[1].1);
+            let (Some(pos1), Some(pos2)) = (
+                point.offset(window[0].0, window[0].1),
+                point.offset(window[1].0, window[1].1),
+            ) else {
+                continue;
+            };
             if loop_coords.contains(&pos1) && loop_coords.contains(&pos2) {
                 start_points.push((pos1, pos2, DIRECTION[i]));
                 //println!("Possible crack in loop wall at {:?}", (pos1, pos2, DIRECTION[i]))
@@ -367,82 +311,56 @@ fn is_inside(
 
     true
 }
+// Two neighbouring nodes and the direction to squeeze between them in
+type Gap = (Pos, Pos, Direction);
+
 // Search for a way out, depth first
 fn path_find(
     pos1: Pos,
     pos2: Pos,
-    dir: Dir,
-    outside_nodes: &HashSet<(i32, i32)>,
-    loop_coords: &HashSet<(i32, i32)>,
+    dir: Direction,
+    outside_nodes: &HashSet<Pos>,
+    loop_coords: &HashSet<Pos>,
     field: &Field,
 ) -> bool {
-    let mut stack = vec![(pos1, pos2, dir)];
-    let mut visited = HashSet::new();
-    while let Some((pos1, pos2, dir)) = stack.pop() {
-        if outside_nodes.contains(&pos1) || outside_nodes.contains(&pos2) {
-            println!("nodes {:?} {:?} are part of outside!", pos1, pos2);
-            return true;
-        }
-        if visited.contains(&(pos1, pos2, dir)) {
-            continue;
-        } else {
-            visited.insert((pos1, pos2, dir));
+    // Squeezing past the outside of the loop or off the field is a way out
+    let is_out = |&(pos1, pos2, _): &Gap| {
+        outside_nodes.contains(&pos1)
+            || outside_nodes.contains(&pos2)
+            || pipe_at(field, pos1).is_none()
+            || pipe_at(field, pos2).is_none()
+    };
+    let successors = |&(pos1, pos2, dir): &Gap| {
+        let (Some(pipe1), Some(pipe2)) = (pipe_at(field, pos1), pipe_at(field, pos2)) else {
+            return Vec::new();
+        };
+        if (loop_coords.contains(&pos1) && !is_opening(dir, pipe1, Order::First))
+            || (loop_coords.contains(&pos1) && !is_opening(dir, pipe2, Order::Second))
+        {
+            return Vec::new();
         }
 
-        if let (Some(pipe1), Some(pipe2)) = (field.index(pos1), field.index(pos2)) {
-            if (loop_coords.contains(&pos1) && !is_opening(dir, pipe1, Order::First))
-                || (loop_coords.contains(&pos1) && !is_opening(dir, pipe2, Order::Second))
-            {
-                //println!("No opening:");
-                //format_node(pipe1, pipe2, dir);
-                continue;
-            } else {
-                //println!("Squeezing between: {:?} {:?} dir {:?}", pipe1 as char, pipe2 as char, dir);
-            }
-
-            let (pos_n1, pos_n2) = next_nodes(pos1, dir);
-            let next_dirs = next_dirs(dir);
-            stack.extend(&[
-                (pos1, pos_n1, next_dirs[0]),
-                (pos_n1, pos_n2, next_dirs[1]),
-                (pos_n2, pos2, next_dirs[2]),
-            ])
-        } else {
-            println!(
-                "One of the nodes {:?} {:?} are outside the field",
-                pos1, pos2
-            );
-            return true;
-        }
-    }
+        let Some((pos_n1, pos_n2)) = next_nodes(pos1, dir) else {
+            return Vec::new();
+        };
+        let next_dirs = next_dirs(dir);
+        vec![
+            (pos1, pos_n1, next_dirs[0]),
+            (pos_n1, pos_n2, next_dirs[1]),
+            (pos_n2, pos2, next_dirs[2]),
+        ]
+    };
 
-    //println!("Could not find a path out from start {:?} {:?} dir {:?}", pos1, pos2, dir);
-    false
+    search::dfs([(pos1, pos2, dir)], successors, is_out).is_some()
 }
 
-fn next_nodes(pos1: Pos, dir: Dir) -> (Pos, Pos) {
-    match dir {
-        Dir::Left => ((pos1.0 - 1, pos1.1), (pos1.0 - 1, pos1.1 - 1)),
-        Dir::Up => ((pos1.0, pos1.1 - 1), (pos1.0 + 1, pos1.1 - 1)),
-        Dir::Right => ((pos1.0 + 1, pos1.1), (pos1.0 + 1, pos1.1 + 1)),
-        Dir::Down => ((pos1.0, pos1.1 + 1), (pos1.0 - 1, pos1.1 + 1)),
-    }
-}
-fn next_dirs(dir: Dir) -> [Dir; 3] {
-    match dir {
-        Dir::Left => [Dir::Down, Dir::Left, Dir::Up],
-        Dir::Up => [Dir::Left, Dir::Up, Dir::Right],
-        Dir::Right => [Dir::Up, Dir::Right, Dir::Down],
-        Dir::Down => [Dir::Right, Dir::Down, Dir::Left],
-    }
+// The two nodes ahead of `pos1`, the second one diagonally
+fn next_nodes(pos1: Pos, dir: Direction) -> Option<(Pos, Pos)> {
+    let pos_n1 = pos1.step(dir)?;
+    Some((pos_n1, pos_n1.step(dir.turn_right())?))
 }
-fn format_node(pipe1: u8, pipe2: u8, dir: Dir) {
-    match dir {
-        Dir::Left => println!(" {}x\n {}x", pipe2 as char, pipe1 as char),
-        Dir::Up => println!(" {}{}\n xx", pipe1 as char, pipe2 as char),
-        Dir::Right => println!(" x{}\n x{}", pipe1 as char, pipe2 as char),
-        Dir::Down => println!(" xx\n {}{}", pipe2 as char, pipe1 as char),
-    }
+fn next_dirs(dir: Direction) -> [Direction; 3] {
+    [dir.turn_left(), dir, dir.turn_right()]
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -450,79 +368,94 @@ enum Order {
     First,
     Second,
 }
-fn is_opening(dir: Dir, pipe: u8, order: Order) -> bool {
+fn is_opening(dir: Direction, pipe: u8, order: Order) -> bool {
     use Order::*;
     match (pipe, order, dir) {
-        (b'|', _, Dir::Down | Dir::Up) => true,
-        (b'-', _, Dir::Left | Dir::Right) => true,
-
-        (b'L', First, Dir::Down) => true,
-        (b'L', Second, Dir::Down) => false,
-        (b'L', First, Dir::Up) => false,
-        (b'L', Second, Dir::Up) => true,
-
-        (b'L', First, Dir::Left) => false,
-        (b'L', Second, Dir::Left) => true,
-        (b'L', First, Dir::Right) => true,
-        (b'L', Second, Dir::Right) => false,
-
-        (b'J', First, Dir::Down) => false,
-        (b'J', Second, Dir::Down) => true,
-        (b'J', First, Dir::Up) => true,
-        (b'J', Second, Dir::Up) => false,
-
-        (b'J', First, Dir::Left) => false,
-        (b'J', Second, Dir::Left) => true,
-        (b'J', First, Dir::Right) => true,
-        (b'J', Second, Dir::Right) => false,
-
-        (b'7', First, Dir::Down) => false,
-        (b'7', Second, Dir::Down) => true,
-        (b'7', First, Dir::Up) => true,
-        (b'7', Second, Dir::Up) => false,
-
-        (b'7', First, Dir::Left) => true,
-        (b'7', Second, Dir::Left) => false,
-        (b'7', First, Dir::Right) => false,
-        (b'7', Second, Dir::Right) => true,
-
-        (b'F', First, Dir::Down) => true,
-        (b'F', Second, Dir::Down) => false,
-        (b'F', First, Dir::Up) => false,
-        (b'F', Second, Dir::Up) => true,
-
-        (b'F', First, Dir::Left) => true,
-        (b'F', Second, Dir::Left) => false,
-        (b'F', First, Dir::Right) => false,
-        (b'F', Second, Dir::Right) => true,
+        (b'|', _, Direction::Down | Direction::Up) => true,
+        (b'-', _, Direction::Left | Direction::Right) => true,
+
+        (b'L', First, Direction::Down) => true,
+        (b'L', Second, Direction::Down) => false,
+        (b'L', First, Direction::Up) => false,
+        (b'L', Second, Direction::Up) => true,
+
+        (b'L', First, Direction::Left) => false,
+        (b'L', Second, Direction::Left) => true,
+        (b'L', First, Direction::Right) => true,
+        (b'L', Second, Direction::Right) => false,
+
+        (b'J', First, Direction::Down) => false,
+        (b'J', Second, Direction::Down) => true,
+        (b'J', First, Direction::Up) => true,
+        (b'J', Second, Direction::Up) => false,
+
+        (b'J', First, Direction::Left) => false,
+        (b'J', Second, Direction::Left) => true,
+        (b'J', First, Direction::Right) => true,
+        (b'J', Second, Direction::Right) => false,
+
+        (b'7', First, Direction::Down) => false,
+        (b'7', Second, Direction::Down) => true,
+        (b'7', First, Direction::Up) => true,
+        (b'7', Second, Direction::Up) => false,
+
+        (b'7', First, Direction::Left) => true,
+        (b'7', Second, Direction::Left) => false,
+        (b'7', First, Direction::Right) => false,
+        (b'7', Second, Direction::Right) => true,
+
+        (b'F', First, Direction::Down) => true,
+        (b'F', Second, Direction::Down) => false,
+        (b'F', First, Direction::Up) => false,
+        (b'F', Second, Direction::Up) => true,
+
+        (b'F', First, Direction::Left) => true,
+        (b'F', Second, Direction::Left) => false,
+        (b'F', First, Direction::Right) => false,
+        (b'F', Second, Direction::Right) => true,
 
         _ => false,
     }
 }
 
-fn solve_part2(input: &str) -> u32 {
-    let (start_pos, mut field) = parse(input);
-    let found_loop = traverse(start_pos, &field);
-    substitute_start_pipe(&found_loop, &mut field);
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = (Pos, Field);
 
-    find_pools(&found_loop, &field)
+    fn parse(input: &str) -> AocResult<Self::Input> {
+        parse(input)
+    }
+
+    fn part1((start_pos, field): &Self::Input, _params: &Params) -> Answer {
+        let found_loop = traverse(*start_pos, field);
+        (found_loop.length / 2).into()
+    }
+
+    fn part2((start_pos, field): &Self::Input, _params: &Params) -> Answer {
+        let mut field = field.clone();
+        let found_loop = traverse(*start_pos, &field);
+        substitute_start_pipe(&found_loop, &mut field);
+
+        find_pools(&found_loop, &field).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::{error::Error, f64::consts::E};
 
-    use crate::get_input;
+    use crate::{answers::assert_answer, get_input, solution::Part};
 
     use super::*;
 
     #[test]
     fn day10_part1_test() {
-        let res = solve_part1(EXAMPLE);
+        let res = Day10::solve_part1(EXAMPLE).unwrap();
         println!("{res}");
         assert_eq!(res, 4);
 
-        let res = solve_part1(EXAMPLE_2);
+        let res = Day10::solve_part1(EXAMPLE_2).unwrap();
         println!("{res}");
         assert_eq!(res, 8);
     }
@@ -530,28 +463,28 @@ mod tests {
     #[test]
     fn day10_part1() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 10)?;
-        let res = solve_part1(&input);
-        println!("day10 Part1 Result: {res}");
+        let res = Day10::solve_part1(&input).unwrap();
+        assert_answer(2023, 10, Part::One, &res);
         Ok(())
     }
 
     #[test]
     fn day10_part2_test() {
-        let res = solve_part2(EXAMPLE_PART2_SMALL);
+        let res = Day10::solve_part2(EXAMPLE_PART2_SMALL).unwrap();
         assert_eq!(res, 4);
-        let res = solve_part2(EXAMPLE_PART2_SMALLEST);
+        let res = Day10::solve_part2(EXAMPLE_PART2_SMALLEST).unwrap();
         assert_eq!(res, 4);
-        let res = solve_part2(EXAMPLE_PART2);
+        let res = Day10::solve_part2(EXAMPLE_PART2).unwrap();
         assert_eq!(res, 8);
-        let res = solve_part2(EXAMPLE_PART2_LARGE);
+        let res = Day10::solve_part2(EXAMPLE_PART2_LARGE).unwrap();
         assert_eq!(res, 10);
     }
 
     #[test]
     fn day10_part2() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 10)?;
-        let res = solve_part2(&input);
-        println!("day10 Part2 Result: {res}");
+        let res = Day10::solve_part2(&input).unwrap();
+        assert_answer(2023, 10, Part::Two, &res);
         Ok(())
     }
 }
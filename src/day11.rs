@@ -1,5 +1,8 @@
 use std::collections::{HashMap, HashSet};
 
+use crate::error::{AocError, AocResult};
+use crate::solution::{Answer, Param, ParamKind, Params, Solution};
+
 const EXAMPLE: &str = r#"...#......
 .......#..
 #.........
@@ -11,31 +14,25 @@ const EXAMPLE: &str = r#"...#......
 .......#..
 #...#....."#;
 
-fn parse(input: &str) -> (Vec<(i64, i64)>, HashSet<usize>, HashSet<usize>) {
+fn parse(input: &str) -> AocResult<Image> {
     let mut filled_rows = HashSet::new();
     let mut filled_columns = HashSet::new();
-    let mut width = 0;
-    let mut height = 0;
-    let galaxies = input
-        .lines()
-        .enumerate()
-        .map(|(row, line)| {
-            let mut galaxies = Vec::new();
-            for (col, c) in line.chars().enumerate() {
-                if c == '#' {
+    let mut galaxies = Vec::new();
+    for (row, line) in input.lines().enumerate() {
+        for (col, c) in line.chars().enumerate() {
+            match c {
+                '#' => {
                     galaxies.push((col as i64, row as i64));
                     filled_rows.insert(row);
                     filled_columns.insert(col);
                 }
-                width = width.max(col + 1);
+                '.' => {}
+                _ => return Err(AocError::at(row + 1, col + 1, format!("unexpected '{c}'"))),
             }
-            height = height.max(row + 1);
-            galaxies
-        })
-        .flatten()
-        .collect::<Vec<_>>();
+        }
+    }
 
-    (galaxies, filled_columns, filled_rows)
+    Ok((galaxies, filled_columns, filled_rows))
 }
 
 fn find_galaxy_distance(
@@ -77,27 +74,52 @@ fn find_galaxy_distance(
     distances.iter().sum()
 }
 
-fn solve_part1(input: &str) -> i64 {
-    let (galaxies, filled_columns, filled_rows) = parse(input);
-    find_galaxy_distance(2, galaxies, &filled_columns, &filled_rows)
+type Image = (Vec<(i64, i64)>, HashSet<usize>, HashSet<usize>);
+
+fn expanded_distance(empty_distance: u32, (galaxies, filled_columns, filled_rows): &Image) -> i64 {
+    find_galaxy_distance(
+        empty_distance.into(),
+        galaxies.clone(),
+        filled_columns,
+        filled_rows,
+    )
 }
 
-fn solve_part2(empty_distance: i64, input: &str) -> i64 {
-    let (galaxies, filled_columns, filled_rows) = parse(input);
-    find_galaxy_distance(empty_distance, galaxies, &filled_columns, &filled_rows)
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Image;
+
+    const PARAMS: &'static [Param] = &[Param {
+        name: "empty_distance",
+        default: 1_000_000,
+        kind: ParamKind::PositiveU32,
+    }];
+
+    fn parse(input: &str) -> AocResult<Self::Input> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Answer {
+        expanded_distance(2, input).into()
+    }
+
+    fn part2(input: &Self::Input, params: &Params) -> Answer {
+        expanded_distance(params.get::<u32>("empty_distance"), input).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::{error::Error, f64::consts::E};
 
-    use crate::get_input;
+    use crate::{answers::assert_answer, get_input, solution::Part};
 
     use super::*;
 
     #[test]
     fn day11_part1_test() {
-        let res = solve_part1(EXAMPLE);
+        let res = Day11::solve_part1(EXAMPLE).unwrap();
         println!("{res}");
         assert_eq!(res, 374);
     }
@@ -105,24 +127,27 @@ mod tests {
     #[test]
     fn day11_part1() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 11)?;
-        let res = solve_part1(&input);
-        println!("day11 Part1 Result: {res}");
+        let res = Day11::solve_part1(&input).unwrap();
+        assert_answer(2023, 11, Part::One, &res);
         Ok(())
     }
 
     #[test]
     fn day11_part2_test() {
-        let res = solve_part2(10, EXAMPLE);
+        let params = Day11::params().with("empty_distance", 10);
+        let res = Day11::solve_part2_with(EXAMPLE, &params).unwrap();
         assert_eq!(res, 1030);
-        let res = solve_part2(100, EXAMPLE);
+        let params = Day11::params().with("empty_distance", 100);
+        let res = Day11::solve_part2_with(EXAMPLE, &params).unwrap();
         assert_eq!(res, 8410);
+        assert!(Day11::params().set("empty_distance", 0).is_err());
     }
 
     #[test]
     fn day11_part2() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 11)?;
-        let res = solve_part2(1_000_000, &input);
-        println!("day11 Part2 Result: {res}");
+        let res = Day11::solve_part2(&input).unwrap();
+        assert_answer(2023, 11, Part::Two, &res);
         Ok(())
     }
 }
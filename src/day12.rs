@@ -2,6 +2,9 @@ use rayon::prelude::*;
 use std::collections::HashMap;
 use std::sync::atomic::AtomicI16;
 
+use crate::error::{parse_in_line, parse_lines, AocError, AocResult};
+use crate::solution::{Answer, Params, Solution};
+
 const EXAMPLE: &str = r#"???.### 1,1,3
 .??..??...?##. 1,1,3
 ?#?#?#?#?#?#?#? 1,3,1,6
@@ -9,46 +12,38 @@ const EXAMPLE: &str = r#"???.### 1,1,3
 ????.######..#####. 1,6,5
 ?###???????? 3,2,1"#;
 
-fn parse(input: &str) -> Vec<(Vec<u8>, Vec<u32>)> {
-    input
-        .lines()
-        .map(|l| {
-            let mut parts = l.split(' ');
-            let record = parts.next().unwrap().as_bytes().iter().copied().collect();
-            let numbers = parts
-                .next()
-                .unwrap()
-                .split(',')
-                .map(str::parse::<u32>)
-                .filter_map(|r| r.ok())
-                .collect();
-            (record, numbers)
-        })
-        .collect()
+// `???.### 1,1,3`
+fn parse_row(line: &str) -> AocResult<(Vec<u8>, Vec<u32>)> {
+    let (record, numbers) = line
+        .split_once(' ')
+        .ok_or_else(|| AocError::in_line(line, &line[line.len()..], "missing group sizes"))?;
+    if let Some(col) = record.find(|c| !matches!(c, '.' | '#' | '?')) {
+        return Err(AocError::in_line(
+            line,
+            &record[col..],
+            format!("unexpected '{}'", &record[col..col + 1]),
+        ));
+    }
+    let numbers = numbers
+        .split(',')
+        .map(|n| parse_in_line(line, n))
+        .collect::<AocResult<_>>()?;
+    Ok((record.as_bytes().to_vec(), numbers))
+}
+
+fn parse(input: &str) -> AocResult<Vec<(Vec<u8>, Vec<u32>)>> {
+    parse_lines(input, parse_row)
 }
 
-fn parse_part2(input: &str) -> Vec<(usize, Vec<u8>, usize, Vec<u32>)> {
-    input
-        .lines()
-        .filter_map(|l| {
-            let mut parts = l.split(' ');
-            let mut record: Vec<u8> = parts.next()?.as_bytes().iter().copied().collect();
-            let mut numbers: Vec<u32> = parts
-                .next()?
-                .split(',')
-                .map(str::parse::<u32>)
-                .filter_map(|r| r.ok())
-                .collect();
-
-            let rec_filler = record.clone();
-            record.extend(
-                (0..4)
-                    .map(|_| [b'?'].iter().chain(rec_filler.iter()))
-                    .flatten(),
-            );
-            let num_filler = numbers.clone();
-            numbers.extend((0..4).map(|_| num_filler.iter()).flatten());
-            Some((rec_filler.len(), record, num_filler.len(), numbers))
+fn unfold(springs: &[(Vec<u8>, Vec<u32>)]) -> Vec<(usize, Vec<u8>, usize, Vec<u32>)> {
+    springs
+        .iter()
+        .map(|(rec_filler, num_filler)| {
+            let mut record = rec_filler.clone();
+            record.extend((0..4).flat_map(|_| [b'?'].iter().chain(rec_filler.iter())));
+            let mut numbers = num_filler.clone();
+            numbers.extend((0..4).flat_map(|_| num_filler.iter()));
+            (rec_filler.len(), record, num_filler.len(), numbers)
         })
         .collect()
 }
@@ -92,9 +87,9 @@ fn find_permutations(
                 //next_s.extend((i..window).map(|_| '#'));
                 valid_permutations += find_permutations(
                     window + 1,
-                    &record,
+                    record,
                     num_index + 1,
-                    &numbers, /* , next_s*/
+                    numbers, /* , next_s*/
                 )
             }
         } else {
@@ -162,9 +157,9 @@ fn find_permutations_cached(
                 //next_s.extend((i..window).map(|_| '#'));
                 valid_permutations += find_permutations_cached(
                     window + 1,
-                    &record,
+                    record,
                     num_index + 1,
-                    &numbers,
+                    numbers,
                     r_size,
                     n_size,
                     cache,
@@ -190,41 +185,51 @@ fn find_permutations_cached(
     valid_permutations
 }
 
-fn solve_part1(input: &str) -> u64 {
-    let springs = parse(input);
+pub struct Day12;
 
-    springs
-        .iter()
-        .map(|(record, numbers)| {
-            //println!(":{} {:?} :", String::from_utf8(record.clone()).unwrap(), numbers);
-            find_permutations(0, &record, 0, &numbers /*, String::default()*/)
-        })
-        .sum()
-}
+impl Solution for Day12 {
+    type Input = Vec<(Vec<u8>, Vec<u32>)>;
 
-fn solve_part2(input: &str) -> u64 {
-    let springs = parse_part2(input);
-    springs
-        .par_iter()
-        .map(|(rec_size, record, num_size, numbers)| {
-            //println!(":{} {:?} :", String::from_utf8(record.clone()).unwrap(), numbers);
-            let mut cache = HashMap::new();
-            find_permutations_cached(0, &record, 0, &numbers, *rec_size, *num_size, &mut cache)
-        })
-        .sum()
+    fn parse(input: &str) -> AocResult<Self::Input> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Answer {
+        input
+            .iter()
+            .map(|(record, numbers)| {
+                //println!(":{} {:?} :", String::from_utf8(record.clone()).unwrap(), numbers);
+                find_permutations(0, record, 0, numbers /*, String::default()*/)
+            })
+            .sum::<u64>()
+            .into()
+    }
+
+    fn part2(input: &Self::Input, _params: &Params) -> Answer {
+        let springs = unfold(input);
+        springs
+            .par_iter()
+            .map(|(rec_size, record, num_size, numbers)| {
+                //println!(":{} {:?} :", String::from_utf8(record.clone()).unwrap(), numbers);
+                let mut cache = HashMap::new();
+                find_permutations_cached(0, record, 0, numbers, *rec_size, *num_size, &mut cache)
+            })
+            .sum::<u64>()
+            .into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::error::Error;
 
-    use crate::get_input;
+    use crate::{answers::assert_answer, get_input, solution::Part};
 
     use super::*;
 
     #[test]
     fn day12_part1_test() {
-        let res = solve_part1(EXAMPLE);
+        let res = Day12::solve_part1(EXAMPLE).unwrap();
         println!("{res}");
         assert_eq!(res, 21);
     }
@@ -232,22 +237,22 @@ mod tests {
     #[test]
     fn day12_part1() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 12)?;
-        let res = solve_part1(&input);
-        println!("day12 Part1 Result: {res}");
+        let res = Day12::solve_part1(&input).unwrap();
+        assert_answer(2023, 12, Part::One, &res);
         Ok(())
     }
 
     #[test]
     fn day12_part2_test() {
-        let res = solve_part2(EXAMPLE);
+        let res = Day12::solve_part2(EXAMPLE).unwrap();
         assert_eq!(res, 525152);
     }
 
     #[test]
     fn day12_part2_sol() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 12)?;
-        let res = solve_part2(&input);
-        println!("day12 Part2 Result: {res}");
+        let res = Day12::solve_part2(&input).unwrap();
+        assert_answer(2023, 12, Part::Two, &res);
         Ok(())
     }
 }
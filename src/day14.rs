@@ -1,4 +1,9 @@
-use std::{collections::HashMap, fmt::Write, path::Display};
+use std::collections::HashMap;
+
+use crate::error::AocResult;
+use crate::geometry::Point;
+use crate::grid::Grid;
+use crate::solution::{Answer, Params, Solution};
 
 const EXAMPLE: &str = r#"O....#....
 O.OO#....#
@@ -11,168 +16,114 @@ O.#..O.#.#
 #....###..
 #OO..#...."#;
 
-#[derive(Debug, Default)]
-struct Pattern {
-    height: usize,
-    width: usize,
-    data: Vec<u8>,
-    buffer: Vec<u8>,
-}
+type Pattern = Grid<u8>;
 
-fn get_index(data: &[u8], width: usize, row: usize, col: usize) -> u8 {
-    let i = row * width + col;
-    *data.get(i).unwrap_or(&0)
-}
+fn tilt_north(pattern: &mut Pattern) {
+    for row in 1..pattern.height() {
+        for col in 0..pattern.width() {
+            if pattern[Point::new(col, row)] != b'O' {
+                continue;
+            }
 
-impl std::fmt::Display for Pattern {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("W: {} H: {}\n", self.width, self.height))?;
-        for r in 0..self.height {
-            for c in 0..self.width {
-                let rock = get_index(&self.data, self.width, r, c);
-                f.write_char(rock as char)?;
+            let mut rock_row = row;
+            for past_row in (0..row).rev() {
+                if pattern[Point::new(col, past_row)] != b'.' {
+                    break;
+                } else {
+                    rock_row = past_row;
+                }
             }
 
-            f.write_char('\n')?;
+            pattern[Point::new(col, row)] = b'.';
+            pattern[Point::new(col, rock_row)] = b'O';
         }
-        Ok(())
     }
 }
 
-impl Pattern {
-    fn rotate(&mut self) {
-        for row in 0..self.height {
-            for col in 0..self.width {
-                let cur_rock = get_index(&self.data, self.width, row, col);
-                let new_row = col;
-                let new_col = self.width - 1 - row;
-                self.buffer[new_row * self.width + new_col] = cur_rock;
-            }
-        }
-        self.data.clear();
-        self.data.extend(self.buffer.iter().copied());
-    }
-
-    fn tilt_north(&mut self) {
-        self.buffer = self.data.clone();
-
-        for row in 1..self.height {
-            for col in 0..self.width {
-                let cur_rock = get_index(&self.data, self.width, row, col);
-                if !(cur_rock == b'O') {
-                    continue;
-                }
-
-                let mut rock_row = row;
-                let orig_idx = row * self.width + col;
-
-                for past_row in (0..row).rev() {
-                    let rock = get_index(&self.buffer, self.width, past_row, col);
-                    if rock != b'.' {
-                        break;
-                    } else {
-                        rock_row = past_row;
-                    }
-                }
-
-                self.buffer[orig_idx] = b'.';
-                self.buffer[rock_row * self.width + col] = b'O';
-            }
-        }
+fn weight(pattern: &Pattern) -> usize {
+    pattern
+        .iter()
+        .filter(|(_, rock)| **rock == b'O')
+        .map(|(pos, _)| pattern.height() - pos.y)
+        .sum()
+}
 
-        self.data.clear();
-        self.data.extend(self.buffer.iter().copied());
+fn cycle(pattern: &mut Pattern) {
+    for _ in 0..4 {
+        tilt_north(pattern);
+        *pattern = pattern.rotate_clockwise();
     }
+}
 
-    fn weight(&mut self) -> usize {
-        let mut weight = 0;
-        for r in 0..self.height {
-            for c in 0..self.width {
-                let rock = get_index(&self.data, self.width, r, c);
-                if rock == b'O' {
-                    weight += self.height - r;
-                }
-            }
+fn cycle_amount(pattern: &mut Pattern, cycles: usize) -> usize {
+    let mut cache: HashMap<Pattern, usize> = HashMap::new();
+
+    let mut found_cycle = 0;
+    for i in 0..cycles {
+        if let Some(cycle) = cache.get(pattern) {
+            let div = (cycles - i) / (i - cycle);
+            found_cycle = div * (i - cycle) + i;
+            break;
+        } else {
+            let start_state = pattern.clone();
+            cycle(pattern);
+            cache.insert(start_state, i);
         }
-        weight
     }
 
-    fn cycle(&mut self) {
-        for _ in 0..4 {
-            self.tilt_north();
-            self.rotate();
-        }
+    for _ in found_cycle..cycles {
+        cycle(pattern);
     }
+    weight(pattern)
+}
 
-    fn cycle_amount(&mut self, cycles: usize) -> usize {
-        let mut cache: HashMap<Vec<u8>, usize> = HashMap::new();
-
-        let mut found_cycle = 0;
-        for i in 0..cycles {
-            if let Some(cycle) = cache.get(&self.data) {
-                let div = (cycles - i) / (i - cycle);
-                found_cycle = div * (i - cycle) + i;
-                break;
-            } else {
-                let start_state = self.data.clone();
-                self.cycle();
-                cache.insert(start_state, i);
-            }
-        }
-
-        for _ in found_cycle..cycles {
-            self.cycle();
-        }
-        self.weight()
-    }
+fn parse(input: &str) -> AocResult<Pattern> {
+    Grid::parse(input, b".#O")
 }
 
-fn parse(input: &str) -> Pattern {
-    let mut pattern = Pattern::default();
-    let mut width = 0;
-    for line in input.lines() {
-        width = width.max(line.len());
-        pattern.data.extend(line.as_bytes().iter().copied());
-        pattern.height += 1;
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Pattern;
+
+    fn parse(input: &str) -> AocResult<Self::Input> {
+        parse(input)
     }
-    pattern.width = width;
-    pattern.buffer = pattern.data.clone();
-    pattern
-}
 
-fn solve_part1(input: &str) -> usize {
-    let mut pattern = parse(input);
-    pattern.tilt_north();
-    pattern.weight()
-}
+    fn part1(input: &Self::Input, _params: &Params) -> Answer {
+        let mut pattern = input.clone();
+        tilt_north(&mut pattern);
+        weight(&pattern).into()
+    }
 
-fn solve_part2(input: &str) -> usize {
-    let mut pattern = parse(input);
-    pattern.cycle_amount(1_000_000_000)
+    fn part2(input: &Self::Input, _params: &Params) -> Answer {
+        let mut pattern = input.clone();
+        cycle_amount(&mut pattern, 1_000_000_000).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::error::Error;
 
-    use crate::get_input;
+    use crate::{answers::assert_answer, get_input, solution::Part};
 
     use super::*;
 
     #[test]
     fn test_cycle() {
-        let mut pattern = parse(EXAMPLE);
-        pattern.cycle();
+        let mut pattern = parse(EXAMPLE).unwrap();
+        cycle(&mut pattern);
         println!("{}", pattern);
-        pattern.cycle();
+        cycle(&mut pattern);
         println!("{}", pattern);
-        pattern.cycle();
+        cycle(&mut pattern);
         println!("{}", pattern);
     }
 
     #[test]
     fn day14_part1_test() {
-        let res = solve_part1(EXAMPLE);
+        let res = Day14::solve_part1(EXAMPLE).unwrap();
         println!("{res}");
         assert_eq!(res, 136)
     }
@@ -180,22 +131,22 @@ mod tests {
     #[test]
     fn day14_part1() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 14)?;
-        let res = solve_part1(&input);
-        println!("day14 Part1 Result: {res}");
+        let res = Day14::solve_part1(&input).unwrap();
+        assert_answer(2023, 14, Part::One, &res);
         Ok(())
     }
 
     #[test]
     fn day14_part2_test() {
-        let res = solve_part2(EXAMPLE);
+        let res = Day14::solve_part2(EXAMPLE).unwrap();
         assert_eq!(res, 64);
     }
 
     #[test]
     fn day14_part2() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 14)?;
-        let res = solve_part2(&input);
-        println!("day14 Part2 Result: {res}");
+        let res = Day14::solve_part2(&input).unwrap();
+        assert_answer(2023, 14, Part::Two, &res);
         Ok(())
     }
 }
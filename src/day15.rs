@@ -1,10 +1,13 @@
+use crate::error::{parse_in_line, AocError, AocResult};
+use crate::solution::{Answer, Params, Solution};
+
 const EXAMPLE: &str = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
 
 fn hash(mut val: u32, chr: u32) -> u32 {
     val += chr;
     val *= 17;
-    let res = val % 256;
-    res
+
+    val % 256
 }
 
 fn hash_str(data: &str) -> u32 {
@@ -13,10 +16,6 @@ fn hash_str(data: &str) -> u32 {
         .fold(0, |acc, byte| hash(acc, *byte as u32))
 }
 
-fn solve_part1(input: &str) -> u32 {
-    input.split(',').map(|elem| hash_str(elem)).sum()
-}
-
 struct Map {
     storage: Vec<Vec<(String, u32)>>,
 }
@@ -32,7 +31,7 @@ impl Map {
         let index = hash_str(key);
         if let Some((_, stored_val)) = self.storage[index as usize]
             .iter_mut()
-            .find(|elem| &elem.0 == key)
+            .find(|elem| elem.0 == key)
         {
             *stored_val = val;
         } else {
@@ -42,7 +41,7 @@ impl Map {
 
     fn remove(&mut self, key: &str) {
         let index = hash_str(key);
-        self.storage[index as usize].retain(|elem| &elem.0 != key)
+        self.storage[index as usize].retain(|elem| elem.0 != key)
     }
 
     fn focusing_power(&self) -> u32 {
@@ -60,63 +59,119 @@ impl Map {
     }
 }
 
-fn solve_part2(input: &str) -> u32 {
+pub enum Operation {
+    Remove,
+    Insert(u32),
+}
+
+pub struct Step {
+    text: String,
+    label: String,
+    operation: Operation,
+}
+
+fn parse_step(input: &str, text: &str) -> AocResult<Step> {
+    let (label, operation) = if let Some(label) = text.strip_suffix('-') {
+        (label, Operation::Remove)
+    } else if let Some((label, focal_length)) = text.split_once('=') {
+        (
+            label,
+            Operation::Insert(parse_in_line(input, focal_length)?),
+        )
+    } else {
+        return Err(AocError::in_line(
+            input,
+            text,
+            format!("step '{text}' has no operation"),
+        ));
+    };
+    Ok(Step {
+        text: text.to_owned(),
+        label: label.to_owned(),
+        operation,
+    })
+}
+
+fn arrange_lenses(steps: &[Step]) -> u32 {
     let mut map = Map::new();
 
-    for operation in input.split(',') {
-        if let Some(label_end_index) = operation.find('-') {
-            let label = &operation[0..label_end_index];
-            map.remove(label);
-        } else if let Some(label_end_index) = operation.find('=') {
-            let label = &operation[0..label_end_index];
-
-            match operation[label_end_index + 1..label_end_index + 2].parse::<u32>() {
-                Ok(val) => map.insert(label, val),
-                Err(err) => println!(
-                    "Cant parse {} to int, because {err}",
-                    &operation[label_end_index + 1..]
-                ),
-            }
+    for step in steps {
+        match step.operation {
+            Operation::Remove => map.remove(&step.label),
+            Operation::Insert(focal_length) => map.insert(&step.label, focal_length),
         }
     }
 
     map.focusing_power()
 }
 
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Vec<Step>;
+
+    // The sequence is a single line, so columns are what locate a bad step
+    fn parse(input: &str) -> AocResult<Self::Input> {
+        let line = input.trim_end();
+        line.split(',')
+            .map(|text| parse_step(line, text).map_err(|e| e.on_line(1)))
+            .collect()
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Answer {
+        input
+            .iter()
+            .map(|step| hash_str(&step.text))
+            .sum::<u32>()
+            .into()
+    }
+
+    fn part2(input: &Self::Input, _params: &Params) -> Answer {
+        arrange_lenses(input).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::error::Error;
 
-    use crate::get_input;
+    use crate::{answers::assert_answer, get_input, solution::Part};
 
     use super::*;
 
     #[test]
     fn day15_part1_test() {
-        let res = solve_part1(EXAMPLE);
+        let res = Day15::solve_part1(EXAMPLE).unwrap();
         println!("{res}");
         assert_eq!(res, 1320)
     }
 
     #[test]
+    fn trailing_newline_is_not_hashed() {
+        let res = Day15::solve_part1(&format!("{EXAMPLE}\n")).unwrap();
+        assert_eq!(res, 1320)
+    }
+
+    #[test]
+    #[ignore = "no accepted answer yet"]
     fn day15_part1() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 15)?;
-        let res = solve_part1(&input);
-        println!("day15 Part1 Result: {res}");
+        let res = Day15::solve_part1(&input).unwrap();
+        assert_answer(2023, 15, Part::One, &res);
         Ok(())
     }
 
     #[test]
     fn day15_part2_test() {
-        let res = solve_part2(EXAMPLE);
+        let res = Day15::solve_part2(EXAMPLE).unwrap();
         assert_eq!(res, 145);
     }
 
     #[test]
     fn day15_part2() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 15)?;
-        let res = solve_part2(&input);
-        println!("day15 Part2 Result: {res}");
+        let res = Day15::solve_part2(&input).unwrap();
+        assert_answer(2023, 15, Part::Two, &res);
         Ok(())
     }
 }
@@ -1,9 +1,8 @@
-use std::{
-    cmp::Reverse,
-    collections::{BinaryHeap, HashMap, HashSet},
-    fmt::Write,
-    str::FromStr,
-};
+use crate::error::AocResult;
+use crate::geometry::{Direction, Point};
+use crate::grid::Grid;
+use crate::search::{self, Found};
+use crate::solution::{Answer, Param, ParamKind, Params, Solution};
 
 const EXAMPLE: &str = r#"2413432311323
 3215453535623
@@ -19,291 +18,200 @@ const EXAMPLE: &str = r#"2413432311323
 2546548887735
 4322674655533"#;
 
-#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd)]
-struct Grid {
-    height: usize,
-    width: usize,
-    data: Vec<u8>,
-}
-
-impl Grid {
-    fn get(&self, x: usize, y: usize) -> u8 {
-        if x > (self.width - 1) || y > (self.height - 1) {
-            return 0;
-        }
-
-        let i = y * self.width + x;
-        self.data[i]
-    }
-
-    fn set(&mut self, x: usize, y: usize, val: u8) {
-        let i = y * self.width + x;
-        if let Some(data) = self.data.get_mut(i) {
-            *data = val;
-        }
-    }
+const EXAMPLE2: &str = r#"111111111111
+999999999991
+999999999991
+999999999991
+999999999991"#;
 
-    fn map(&mut self, f: impl Fn(&mut u8)) {
-        self.data.iter_mut().for_each(f);
-    }
-}
+type Pos = Point<usize>;
 
-impl FromStr for Grid {
-    type Err = ();
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let mut grid = Grid::default();
-        let mut width = 0;
-        for line in input.lines() {
-            width = width.max(line.len());
-            grid.data.extend(line.as_bytes().iter().copied());
-            grid.height += 1;
-        }
-        grid.width = width;
-        Ok(grid)
-    }
+// How many blocks a crucible has to go straight before it can turn or stop,
+// and how many it can go straight at most
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Crucible {
+    min_run: usize,
+    max_run: usize,
 }
 
-impl std::fmt::Display for Grid {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("W: {} H: {}\n", self.width, self.height))?;
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let data = self.get(x, y);
-                f.write_char((data + b'0') as char)?;
-            }
-            f.write_char('\n')?;
-        }
-        Ok(())
-    }
-}
+const CRUCIBLE: Crucible = Crucible {
+    min_run: 1,
+    max_run: 3,
+};
 
-type Pos = (usize, usize);
-type Dir = (isize, isize);
+const ULTRA_CRUCIBLE: Crucible = Crucible {
+    min_run: 4,
+    max_run: 10,
+};
 
-const UP: Dir = (0, -1);
-const DOWN: Dir = (0, 1);
-const RIGHT: Dir = (1, 0);
-const LEFT: Dir = (-1, 0);
-const NONE: Dir = (0, 0);
+impl Crucible {
+    fn possible(self, dir: Direction, consecutive: usize) -> [Option<(Direction, usize)>; 3] {
+        let can_turn = consecutive >= self.min_run;
+        [
+            can_turn.then_some((dir.turn_left(), 1)),
+            (consecutive < self.max_run).then_some((dir, consecutive + 1)),
+            can_turn.then_some((dir.turn_right(), 1)),
+        ]
+    }
 
-fn format_dir(dir: Dir) -> u8 {
-    match dir {
-        UP => b'^' - b'0',
-        DOWN => b'v' - b'0',
-        RIGHT => b'>' - b'0',
-        LEFT => b'<' - b'0',
-        _ => 0,
+    fn can_stop(self, consecutive: usize) -> bool {
+        consecutive >= self.min_run
     }
 }
 
-fn move_in_dir(pos: Pos, dir: Dir) -> Pos {
-    (
-        (pos.0 as isize + dir.0) as usize,
-        (pos.1 as isize + dir.1) as usize,
-    )
+// Where the crucible is, where it is heading and how many blocks it has
+// gone straight in that direction
+type State = (Pos, Direction, usize);
+
+// The cheapest way from the top left to the bottom right, the grid holds the
+// heat loss of every block and has at least one
+fn traverse(grid: &Grid<u8>, crucible: Crucible) -> Option<Found<State, usize>> {
+    let start = Point::new(0, 0);
+    let end = Point::new(grid.width() - 1, grid.height() - 1);
+    let runs = crucible.max_run + 1;
+    let key = |&(pos, dir, consecutive): &State| {
+        ((pos.y * grid.width() + pos.x) * 4 + dir as usize) * runs + consecutive
+    };
+    let len = grid.width() * grid.height() * 4 * runs;
+
+    let starts = [Direction::Right, Direction::Down].map(|dir| (start, dir, 0));
+    let successors = |&(pos, dir, consecutive): &State| {
+        crucible
+            .possible(dir, consecutive)
+            .into_iter()
+            .flatten()
+            .filter_map(move |(next_dir, next_consecutive)| {
+                let next_pos = grid.step(pos, next_dir)?;
+                let heat_loss = grid[next_pos] as usize;
+                Some(((next_pos, next_dir, next_consecutive), heat_loss))
+            })
+    };
+    let is_goal = |&(pos, _, consecutive): &State| pos == end && crucible.can_stop(consecutive);
+    search::dijkstra_dense(len, key, starts, successors, is_goal)
 }
 
-fn possible(dir: Dir, consecutive: usize) -> [(Dir, usize); 3] {
-    match dir {
-        UP => [
-            (LEFT, 1),
-            if consecutive < 3 {
-                (UP, consecutive + 1)
-            } else {
-                (NONE, 0)
-            },
-            (RIGHT, 1),
-        ],
-        DOWN => [
-            (RIGHT, 1),
-            if consecutive < 3 {
-                (DOWN, consecutive + 1)
-            } else {
-                (NONE, 0)
-            },
-            (LEFT, 1),
-        ],
-        RIGHT => [
-            (UP, 1),
-            if consecutive < 3 {
-                (RIGHT, consecutive + 1)
-            } else {
-                (NONE, 0)
-            },
-            (DOWN, 1),
-        ],
-        LEFT => [
-            (DOWN, 1),
-            if consecutive < 3 {
-                (LEFT, consecutive + 1)
-            } else {
-                (NONE, 0)
-            },
-            (UP, 1),
-        ],
-        _ => unreachable!(),
+// The heat loss map with the path drawn onto it as arrows
+fn render_path(grid: &Grid<u8>, path: &[State]) -> String {
+    let mut path_grid = printable(grid);
+    for &(pos, dir, _) in path {
+        path_grid.set(pos, dir.arrow() as u8);
     }
+    if let Some(&(start, ..)) = path.first() {
+        path_grid.set(start, b'@');
+    }
+    path_grid.to_string()
 }
 
-#[derive(Debug, Clone, PartialEq)]
-struct State {
-    pos: Pos,
-    dir: Dir,
-    consecutive: usize,
-    heat_loss: usize,
-    grid: Grid,
-}
-
-impl Eq for State {}
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(&other))
-    }
+fn printable(grid: &Grid<u8>) -> Grid<u8> {
+    grid.map(|v| v + b'0')
 }
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        (usize::MAX - self.heat_loss).cmp(&(usize::MAX - other.heat_loss))
+fn least_heat_loss(grid: &Grid<u8>, crucible: Crucible, params: &Params) -> Answer {
+    let Some(found) = traverse(grid, crucible) else {
+        return Answer::Unsolved;
+    };
+    if params.get::<i64>("show_path") != 0 {
+        eprintln!("{}", render_path(grid, &found.path));
+        eprintln!(
+            "Cache Hits: {}/{}",
+            found.stats.cache_hits, found.stats.expanded
+        );
     }
+    found.cost.into()
 }
 
-fn traverse(orig_grid: &Grid, start: Pos, end: Pos) -> u32 {
-    let mut min_heat_loss = usize::MAX;
-    let mut stack = Vec::new();
-    let mut visited = HashMap::new();
-    let mut visits = 0;
-    let mut cache_hits = 0;
-    let mut next_grid = orig_grid.clone();
-    next_grid.set(start.0, start.1, b'@' - b'0');
-    stack.push(State {
-        pos: start,
-        dir: RIGHT,
-        consecutive: 1,
-        heat_loss: 0,
-        grid: next_grid.clone(),
-    });
-    stack.push(State {
-        pos: start,
-        dir: DOWN,
-        consecutive: 1,
-        heat_loss: 0,
-        grid: next_grid,
-    });
+pub struct Day17;
 
-    let mut min_grid = orig_grid.clone();
-    let mut working_stack = Vec::new();
-    while let Some(State {
-        pos,
-        dir,
-        consecutive,
-        heat_loss,
-        mut grid,
-    }) = stack.pop()
-    {
-        visits += 1;
-        if let Some(former_heatloss) = visited.get_mut(&(pos, dir, consecutive)) {
-            if *former_heatloss >= heat_loss {
-                *former_heatloss = heat_loss;
-            } else {
-                cache_hits += 1;
-                continue;
-            }
-        } else {
-            visited.insert((pos, dir, consecutive), heat_loss);
-        }
+impl Solution for Day17 {
+    type Input = Grid<u8>;
 
-        if heat_loss > min_heat_loss {
-            continue;
-        }
-        //grid.set(pos.0, pos.1, b'@' - b'0');
-        grid.set(pos.0, pos.1, format_dir(dir));
+    const PARAMS: &'static [Param] = &[Param {
+        name: "show_path",
+        default: 0,
+        kind: ParamKind::Flag,
+    }];
 
-        if pos == end {
-            if heat_loss < min_heat_loss {
-                min_heat_loss = heat_loss;
-                min_grid = grid.clone();
-            }
-            break;
-        }
-
-        working_stack.clear();
-        for (next_dir, next_consecutive) in possible(dir, consecutive) {
-            let next_pos = move_in_dir(pos, next_dir);
-            let current_heat_loss = orig_grid.get(next_pos.0, next_pos.1);
-            if next_dir == NONE || current_heat_loss == 0 {
-                continue;
-            }
-            let next_heat_loss = current_heat_loss as usize + heat_loss;
-            //print!("{}={} ", (b'0' + format_dir(next_dir)) as char, next_heat_loss);
-            working_stack.push(State {
-                pos: next_pos,
-                dir: next_dir,
-                consecutive: next_consecutive,
-                heat_loss: next_heat_loss,
-                grid: grid.clone(),
-            });
-        }
-        //println!("");
-        working_stack.sort();
-        stack.extend(working_stack.iter().cloned());
+    fn parse(input: &str) -> AocResult<Self::Input> {
+        Grid::parse_with(input, |b| b.is_ascii_digit().then(|| b - b'0'))?.non_empty()
     }
 
-    println!("{min_grid}");
-    println!("Cache Hits: {cache_hits}/{visits}");
-    min_heat_loss as u32
-}
-
-fn solve_part1(input: &str) -> u32 {
-    let mut grid = Grid::from_str(input).unwrap();
-    grid.map(|v| *v -= b'0');
-    println!("{grid}");
-    traverse(&grid, (0, 0), (grid.width - 1, grid.height - 1))
-}
+    fn part1(grid: &Self::Input, params: &Params) -> Answer {
+        least_heat_loss(grid, CRUCIBLE, params)
+    }
 
-fn solve_part2(input: &str) -> u32 {
-    0
+    fn part2(grid: &Self::Input, params: &Params) -> Answer {
+        least_heat_loss(grid, ULTRA_CRUCIBLE, params)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::error::Error;
 
-    use crate::get_input;
+    use crate::{answers::assert_answer, get_input, solution::Part};
 
     use super::*;
 
+    #[test]
+    fn day17_parse_errors() {
+        let err = Day17::parse("").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 1: expected a grid, found no tiles"
+        );
+    }
+
     #[test]
     fn day17_part1_test() {
-        let res = solve_part1(EXAMPLE);
+        let res = Day17::solve_part1(EXAMPLE).unwrap();
         println!("{res}");
         assert_eq!(res, 102)
     }
 
     #[test]
+    fn day17_path() {
+        let grid = Day17::parse(EXAMPLE).unwrap();
+        let found = traverse(&grid, CRUCIBLE).unwrap();
+        let heat_loss: usize = found.path[1..]
+            .iter()
+            .map(|&(pos, ..)| grid[pos] as usize)
+            .sum();
+        assert_eq!(heat_loss, found.cost);
+
+        let rendered = render_path(&grid, &found.path);
+        assert!(rendered.starts_with("@>>"));
+        assert!(rendered.lines().last().unwrap().ends_with(['>', 'v']));
+    }
+
+    #[test]
+    #[ignore = "no accepted answer yet"]
     fn day17_part1() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 17)?;
-        let res = solve_part1(&input);
-        println!("day17 Part1 Result: {res}");
+        let res = Day17::solve_part1(&input).unwrap();
+        assert_answer(2023, 17, Part::One, &res);
         Ok(())
     }
 
     // #[test]
     // fn day17_part1_recursive() {
-    //     let res = solve_part1(&input);
+    //     let res = Day17::solve_part1(&input).unwrap();
     //     assert_eq!(res, 102);
     // }
 
     #[test]
     fn day17_part2_test() {
-        let res = solve_part2(EXAMPLE);
-        assert_eq!(res, 51);
+        let res = Day17::solve_part2(EXAMPLE).unwrap();
+        assert_eq!(res, 94);
+        let res = Day17::solve_part2(EXAMPLE2).unwrap();
+        assert_eq!(res, 71);
     }
 
     #[test]
+    #[ignore = "no accepted answer yet"]
     fn day17_part2() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 17)?;
-        let res = solve_part2(&input);
-        println!("day17 Part2 Result: {res}");
+        let res = Day17::solve_part2(&input).unwrap();
+        assert_answer(2023, 17, Part::Two, &res);
         Ok(())
     }
 }
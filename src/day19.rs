@@ -1,6 +1,12 @@
-use std::{str::FromStr, collections::HashMap};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::{Display, Write},
+};
 
-const EXAMPLE : &str = r#"px{a<2006:qkq,m>2090:A,rfg}
+use crate::error::{parse_in_line, AocError, AocResult};
+use crate::solution::{Answer, Params, Solution};
+
+const EXAMPLE: &str = r#"px{a<2006:qkq,m>2090:A,rfg}
 pv{a>1716:R,A}
 lnx{m>1548:A,A}
 rfg{s<537:gd,x>2440:R,A}
@@ -18,10 +24,42 @@ hdj{m>838:A,pv}
 {x=2461,m=1339,a=466,s=291}
 {x=2127,m=1623,a=2188,s=1013}"#;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Category {
+    X,
+    M,
+    A,
+    S,
+}
+
+impl Category {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            'x' => Some(Category::X),
+            'm' => Some(Category::M),
+            'a' => Some(Category::A),
+            's' => Some(Category::S),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let c = match self {
+            Category::X => 'x',
+            Category::M => 'm',
+            Category::A => 'a',
+            Category::S => 's',
+        };
+        write!(f, "{c}")
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
-struct Part {
+pub struct Part {
     x: usize,
-    m: usize, 
+    m: usize,
     a: usize,
     s: usize,
 }
@@ -30,84 +68,174 @@ impl Part {
     fn rating(&self) -> usize {
         self.x + self.m + self.a + self.s
     }
+
+    fn get(&self, category: Category) -> usize {
+        match category {
+            Category::X => self.x,
+            Category::M => self.m,
+            Category::A => self.a,
+            Category::S => self.s,
+        }
+    }
+}
+
+// Every part with ratings in the ranges, each one is `start..end`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PartRange {
+    x: (usize, usize),
+    m: (usize, usize),
+    a: (usize, usize),
+    s: (usize, usize),
 }
 
-impl FromStr for Part {
-    type Err = String;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let s = s.strip_prefix('{').ok_or(format!("Could not remove prefix '{{': {:?}", s))?;
-        let s = s.strip_suffix('}').ok_or(format!("Could not remove prefix '}}': {:?}", s))?;
-
-        let mut part =  Part { x:0, m:0, a:0, s:0 };
-        for elem in s.split(',') {
-            let mut elem_split = elem.split('=');
-            let index = elem_split.next().unwrap();
-            let num = elem_split.next().unwrap().parse::<usize>().map_err(|_| format!("Could not parse num from {elem}"))?;
-            match index {
-                "x" => part.x = num,
-                "m" => part.m = num,
-                "a" => part.a = num,
-                "s" => part.s = num,
-                _ => unreachable!()
+impl PartRange {
+    // Ratings go from 1 to 4000
+    const ALL: PartRange = PartRange {
+        x: (1, 4001),
+        m: (1, 4001),
+        a: (1, 4001),
+        s: (1, 4001),
+    };
+
+    fn get_mut(&mut self, category: Category) -> &mut (usize, usize) {
+        match category {
+            Category::X => &mut self.x,
+            Category::M => &mut self.m,
+            Category::A => &mut self.a,
+            Category::S => &mut self.s,
+        }
+    }
+
+    // A copy with the range of `category` narrowed to `start..end`, or
+    // `None` if nothing is left
+    fn narrowed(mut self, category: Category, start: usize, end: usize) -> Option<PartRange> {
+        let range = self.get_mut(category);
+        *range = (range.0.max(start), range.1.min(end));
+        (range.0 < range.1).then_some(self)
+    }
+
+    fn combinations(&self) -> usize {
+        [self.x, self.m, self.a, self.s]
+            .iter()
+            .map(|(start, end)| end - start)
+            .product()
+    }
+}
+
+// `{x=787,m=2655,a=1222,s=2876}`
+fn parse_part(line: &str) -> AocResult<Part> {
+    let ratings = line
+        .strip_prefix('{')
+        .and_then(|s| s.strip_suffix('}'))
+        .ok_or_else(|| AocError::in_line(line, line, "expected '{...}' around the ratings"))?;
+
+    let mut part = Part {
+        x: 0,
+        m: 0,
+        a: 0,
+        s: 0,
+    };
+    for elem in ratings.split(',') {
+        let (index, num) = elem
+            .split_once('=')
+            .ok_or_else(|| AocError::in_line(line, elem, "expected '<category>=<rating>'"))?;
+        let num = parse_in_line(line, num)?;
+        match index {
+            "x" => part.x = num,
+            "m" => part.m = num,
+            "a" => part.a = num,
+            "s" => part.s = num,
+            _ => {
+                return Err(AocError::in_line(
+                    line,
+                    index,
+                    format!("unknown category '{index}'"),
+                ))
             }
         }
-        Ok(part)
     }
+    Ok(part)
 }
 
 #[derive(Debug, Clone)]
 struct Rule {
-    elem: char,
+    category: Category,
     op: char,
     threshold: usize,
-    res: String
+    res: String,
 }
 
 impl Rule {
-    fn process<'a>(&'a self, part: Part) -> Option<&'a str> {
-        let value = match self.elem {
-            'x' => part.x,
-            'm' => part.m,
-            'a' => part.a,
-            's' => part.s,
-            _ => unreachable!()
-        };
+    fn process(&self, part: Part) -> Option<&str> {
+        let value = part.get(self.category);
 
         let is_true = match self.op {
             '>' => value > self.threshold,
             '<' => value < self.threshold,
-            _ => unreachable!()
+            _ => unreachable!(),
         };
 
-        if is_true {Some(self.res.as_str())} else {None}
+        if is_true {
+            Some(self.res.as_str())
+        } else {
+            None
+        }
     }
-}
-
-impl FromStr for Rule {
-    type Err = String;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split(':');
-        let params = parts.next().unwrap();
-        let res = parts.next().unwrap();
 
-        let mut rule = Rule{elem: ' ', op: ' ', threshold: 0, res: res.to_owned()};
-        rule.elem = params[0..1].chars().next().unwrap();
-        rule.op = params[1..2].chars().next().unwrap();
-        rule.threshold = params[2..].parse::<usize>().unwrap();
+    // The parts of `range` this rule sends on to `res` and the ones it lets
+    // through to the next rule
+    fn split(&self, range: PartRange) -> (Option<PartRange>, Option<PartRange>) {
+        let (matched, unmatched) = match self.op {
+            '>' => ((self.threshold + 1, usize::MAX), (0, self.threshold + 1)),
+            '<' => ((0, self.threshold), (self.threshold, usize::MAX)),
+            _ => unreachable!(),
+        };
+        (
+            range.narrowed(self.category, matched.0, matched.1),
+            range.narrowed(self.category, unmatched.0, unmatched.1),
+        )
+    }
+}
 
-        Ok(rule)
+impl Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}{}", self.category, self.op, self.threshold)
     }
 }
 
+// `a<2006:qkq`
+fn parse_rule(line: &str, rule: &str) -> AocResult<Rule> {
+    let (params, res) = rule
+        .split_once(':')
+        .ok_or_else(|| AocError::in_line(line, rule, "expected '<condition>:<target>'"))?;
+
+    let mut chars = params.chars();
+    let category = chars
+        .next()
+        .and_then(Category::from_char)
+        .ok_or_else(|| AocError::in_line(line, params, "expected one of 'x', 'm', 'a' or 's'"))?;
+    let op = chars
+        .next()
+        .filter(|c| "<>".contains(*c))
+        .ok_or_else(|| AocError::in_line(line, &params[1..], "expected '<' or '>'"))?;
+
+    Ok(Rule {
+        category,
+        op,
+        threshold: parse_in_line(line, &params[2..])?,
+        res: res.to_owned(),
+    })
+}
+
 #[derive(Debug)]
-struct Machine {
+pub struct Machine {
     name: String,
     rules: Vec<Rule>,
     default: String,
 }
 
 impl Machine {
-    fn process<'a>(&'a self, part: Part) -> &'a str {
+    fn process(&self, part: Part) -> &str {
         for rule in self.rules.iter() {
             if let Some(res) = rule.process(part) {
                 return res;
@@ -115,68 +243,371 @@ impl Machine {
         }
         self.default.as_str()
     }
+
+    // Where the rules send parts, the fallback last
+    fn targets(&self) -> impl Iterator<Item = &str> {
+        self.rules
+            .iter()
+            .map(|rule| rule.res.as_str())
+            .chain([self.default.as_str()])
+    }
+}
+
+// `px{a<2006:qkq,m>2090:A,rfg}`
+fn parse_machine(line: &str) -> AocResult<Machine> {
+    let (name, rest) = line
+        .split_once('{')
+        .ok_or_else(|| AocError::in_line(line, line, "could not find start '{'"))?;
+    let rest = rest
+        .strip_suffix('}')
+        .ok_or_else(|| AocError::in_line(line, &line[line.len()..], "missing closing '}'"))?;
+
+    let mut machine = Machine {
+        name: name.to_owned(),
+        rules: Vec::default(),
+        default: String::default(),
+    };
+
+    let mut rules = rest.split(',').collect::<Vec<_>>();
+    machine.default = rules.pop().unwrap_or_default().to_owned();
+    for r in rules {
+        machine.rules.push(parse_rule(line, r)?);
+    }
+
+    Ok(machine)
+}
+
+type System = (HashMap<String, Machine>, Vec<Part>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Issue {
+    MissingTarget { workflow: String, target: String },
+    Unreachable(String),
+    // Each workflow sends parts to the next one, the last back to the first
+    Cycle(Vec<String>),
+    // `rule` indexes the rules, the fallback comes right after them
+    DeadRule { workflow: String, rule: usize },
+}
+
+impl Issue {
+    // Parts could get lost or loop forever
+    fn is_fatal(&self) -> bool {
+        matches!(self, Issue::MissingTarget { .. } | Issue::Cycle(_))
+    }
+}
+
+impl Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Issue::MissingTarget { workflow, target } => {
+                write!(
+                    f,
+                    "workflow '{workflow}' sends parts to unknown workflow '{target}'"
+                )
+            }
+            Issue::Unreachable(workflow) => write!(f, "workflow '{workflow}' is never reached"),
+            Issue::Cycle(workflows) => {
+                write!(
+                    f,
+                    "workflows loop: {} -> {}",
+                    workflows.join(" -> "),
+                    workflows[0]
+                )
+            }
+            Issue::DeadRule { workflow, rule } => {
+                write!(
+                    f,
+                    "rule {} of workflow '{workflow}' can never match",
+                    rule + 1
+                )
+            }
+        }
+    }
 }
 
-impl FromStr for Machine {
-    type Err = String;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split('{');
-        let name = parts.next().ok_or(format!("could not find start '{{' in {s}"))?;
-        let rest = parts.next().ok_or(format!("could not find rest of the rules in {s}"))?.split(',');
+fn is_terminal(name: &str) -> bool {
+    name == "A" || name == "R"
+}
 
-        let mut machine = Machine{name: name.to_owned(), rules: Vec::default(), default: String::default()};
+fn validate(machines: &HashMap<String, Machine>) -> Vec<Issue> {
+    let mut names = machines.keys().map(String::as_str).collect::<Vec<_>>();
+    names.sort_unstable();
+    let mut issues = Vec::new();
+
+    for &name in &names {
+        for target in machines[name].targets() {
+            if !is_terminal(target) && !machines.contains_key(target) {
+                issues.push(Issue::MissingTarget {
+                    workflow: name.to_owned(),
+                    target: target.to_owned(),
+                });
+            }
+        }
+    }
 
-        for r in rest {
-            if r.contains('}') {
-                let r = r.strip_suffix('}').unwrap();
-                machine.default = r.to_owned();
-            } else {
-                let rule = Rule::from_str(r)?;
-                machine.rules.push(rule)
+    let mut reached = HashSet::from(["in"]);
+    let mut stack = vec!["in"];
+    while let Some(name) = stack.pop() {
+        for target in machines.get(name).into_iter().flat_map(Machine::targets) {
+            if reached.insert(target) {
+                stack.push(target);
             }
         }
+    }
+    for &name in &names {
+        if !reached.contains(name) {
+            issues.push(Issue::Unreachable(name.to_owned()));
+        }
+    }
 
-        Ok(machine)
+    let mut finished = HashSet::new();
+    for &name in &names {
+        find_cycles(machines, name, &mut Vec::new(), &mut finished, &mut issues);
     }
+
+    for &name in &names {
+        let machine = &machines[name];
+        let mut rest = Some(PartRange::ALL);
+        for (i, rule) in machine.rules.iter().enumerate() {
+            let matched = rest.and_then(|range| {
+                let (matched, unmatched) = rule.split(range);
+                rest = unmatched;
+                matched
+            });
+            if matched.is_none() {
+                issues.push(Issue::DeadRule {
+                    workflow: name.to_owned(),
+                    rule: i,
+                });
+            }
+        }
+        if rest.is_none() {
+            issues.push(Issue::DeadRule {
+                workflow: name.to_owned(),
+                rule: machine.rules.len(),
+            });
+        }
+    }
+    issues
 }
 
-fn parse(input: &str) -> (HashMap<String, Machine>, Vec<Part>) {
-    let mut machines = HashMap::new();
+// Depth first, `path` holds the workflows that lead to `name`
+fn find_cycles<'a>(
+    machines: &'a HashMap<String, Machine>,
+    name: &'a str,
+    path: &mut Vec<&'a str>,
+    finished: &mut HashSet<&'a str>,
+    issues: &mut Vec<Issue>,
+) {
+    if finished.contains(name) {
+        return;
+    }
+    if let Some(start) = path.iter().position(|&n| n == name) {
+        let cycle = path[start..].iter().map(|n| n.to_string()).collect();
+        issues.push(Issue::Cycle(cycle));
+        return;
+    }
+    let Some(machine) = machines.get(name) else {
+        return;
+    };
 
-    let mut lines = input.lines();
-    for line in &mut lines {
-        if line.is_empty() { break }
+    path.push(name);
+    for target in machine.targets() {
+        find_cycles(machines, target, path, finished, issues);
+    }
+    path.pop();
+    finished.insert(name);
+}
 
-        match Machine::from_str(line) {
-            Ok(machine) => {machines.insert(machine.name.clone(), machine);},
-            Err(e) => {println!("Could not parse line: {e}");}
+// The workflows as a Graphviz graph, every rule is an edge labelled with
+// its condition
+fn to_dot(machines: &HashMap<String, Machine>) -> String {
+    let mut names = machines.keys().collect::<Vec<_>>();
+    names.sort_unstable();
+
+    let mut out = String::from("digraph workflows {\n");
+    out.push_str("    \"in\" [shape=doublecircle];\n");
+    out.push_str("    \"A\" [shape=box, color=green];\n");
+    out.push_str("    \"R\" [shape=box, color=red];\n");
+    for name in names {
+        let machine = &machines[name];
+        for rule in machine.rules.iter() {
+            writeln!(
+                out,
+                "    \"{name}\" -> \"{}\" [label=\"{rule}\"];",
+                rule.res
+            )
+            .unwrap();
         }
+        writeln!(out, "    \"{name}\" -> \"{}\";", machine.default).unwrap();
+    }
+    out.push_str("}\n");
+    out
+}
+
+const DOT_FILE: &str = "day19.dot";
+
+fn parse(input: &str) -> AocResult<System> {
+    let mut machines = HashMap::new();
+    let mut machine_lines = HashMap::new();
+
+    let mut lines = input.lines().enumerate();
+    for (i, line) in &mut lines {
+        if line.is_empty() {
+            break;
+        }
+
+        let machine = parse_machine(line).map_err(|e| e.on_line(i + 1))?;
+        machine_lines.insert(machine.name.clone(), i + 1);
+        machines.insert(machine.name.clone(), machine);
+    }
+    if !machines.contains_key("in") {
+        return Err(AocError::new("missing the 'in' workflow"));
+    }
+
+    // The other issues are warnings, the runner shows them
+    if let Some(issue) = validate(&machines).into_iter().find(Issue::is_fatal) {
+        let workflow = match &issue {
+            Issue::MissingTarget { workflow, .. } => workflow,
+            Issue::Cycle(workflows) => &workflows[0],
+            _ => unreachable!(),
+        };
+        return Err(AocError::new(issue.to_string()).on_line(machine_lines[workflow]));
     }
 
     let mut parts = Vec::new();
-    for line in lines {
-        match Part::from_str(line) {
-            Ok(part) => parts.push(part),
-            Err(e) => println!("Could not parse part from line: {e}"),
+    for (i, line) in lines {
+        parts.push(parse_part(line).map_err(|e| e.on_line(i + 1))?);
+    }
+
+    Ok((machines, parts))
+}
+
+// Every workflow is flattened into single comparisons, the accept and reject
+// nodes come first
+type NodeId = usize;
+
+const ACCEPT: NodeId = 0;
+const REJECT: NodeId = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Node {
+    Accept,
+    Reject,
+    // Parts rated below `threshold` go on to `below`, the others to `rest`
+    Test {
+        category: Category,
+        threshold: usize,
+        below: NodeId,
+        rest: NodeId,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct DecisionTree {
+    nodes: Vec<Node>,
+    root: NodeId,
+}
+
+impl DecisionTree {
+    // The workflows have to be free of cycles and missing targets, which
+    // `parse` makes sure of
+    fn compile(machines: &HashMap<String, Machine>) -> Self {
+        let mut tree = DecisionTree {
+            nodes: vec![Node::Accept, Node::Reject],
+            root: REJECT,
+        };
+        let mut compiled = HashMap::new();
+        let mut tests = HashMap::new();
+        tree.root = tree.compile_workflow(machines, "in", &mut compiled, &mut tests);
+        tree
+    }
+
+    fn compile_workflow(
+        &mut self,
+        machines: &HashMap<String, Machine>,
+        name: &str,
+        compiled: &mut HashMap<String, NodeId>,
+        tests: &mut HashMap<Node, NodeId>,
+    ) -> NodeId {
+        match name {
+            "A" => return ACCEPT,
+            "R" => return REJECT,
+            _ => {}
         }
+        if let Some(&id) = compiled.get(name) {
+            return id;
+        }
+
+        // Built back to front, each rule falls through to the one after it
+        let machine = &machines[name];
+        let mut next = self.compile_workflow(machines, &machine.default, compiled, tests);
+        for rule in machine.rules.iter().rev() {
+            let matched = self.compile_workflow(machines, &rule.res, compiled, tests);
+            // Both ways lead to the same place, so the rule can be skipped
+            if matched == next {
+                continue;
+            }
+            let (threshold, below, rest) = match rule.op {
+                '<' => (rule.threshold, matched, next),
+                '>' => (rule.threshold + 1, next, matched),
+                _ => unreachable!(),
+            };
+            let test = Node::Test {
+                category: rule.category,
+                threshold,
+                below,
+                rest,
+            };
+            next = *tests.entry(test).or_insert_with(|| {
+                self.nodes.push(test);
+                self.nodes.len() - 1
+            });
+        }
+        compiled.insert(name.to_owned(), next);
+        next
     }
 
-    (machines, parts)
+    fn accepts(&self, part: &Part) -> bool {
+        let mut id = self.root;
+        loop {
+            match self.nodes[id] {
+                Node::Accept => return true,
+                Node::Reject => return false,
+                Node::Test {
+                    category,
+                    threshold,
+                    below,
+                    rest,
+                } => {
+                    id = if part.get(category) < threshold {
+                        below
+                    } else {
+                        rest
+                    }
+                }
+            }
+        }
+    }
 }
 
-fn solve_part1(input: &str) -> usize {
-    let (machines, parts) = parse(input);
-    
+fn accepted_rating((machines, parts): &System) -> usize {
+    let tree = DecisionTree::compile(machines);
+    parts
+        .iter()
+        .filter(|part| tree.accepts(part))
+        .map(Part::rating)
+        .sum()
+}
+
+// Walks the workflows by name for every part
+fn interpreted_rating((machines, parts): &System) -> usize {
     let mut rating = 0;
     for part in parts.iter() {
         let mut res = "in";
+        // `parse` makes sure every target exists
         while res != "A" && res != "R" {
-            if let Some(machine) = machines.get(res) {
-                res = machine.process(*part);
-            } else {
-                println!("Could not find machine: {res}");
-                break;
-            }
+            res = machines[res].process(*part);
         }
 
         if res == "A" {
@@ -186,17 +617,80 @@ fn solve_part1(input: &str) -> usize {
     rating
 }
 
+// How many of the parts in `range` end up accepted when they arrive at `name`
+fn accepted_in(machines: &HashMap<String, Machine>, name: &str, range: PartRange) -> usize {
+    match name {
+        "A" => return range.combinations(),
+        "R" => return 0,
+        _ => {}
+    }
+    let machine = &machines[name];
+
+    let mut accepted = 0;
+    let mut rest = Some(range);
+    for rule in machine.rules.iter() {
+        let Some(range) = rest else {
+            break;
+        };
+        let (matched, unmatched) = rule.split(range);
+        if let Some(matched) = matched {
+            accepted += accepted_in(machines, &rule.res, matched);
+        }
+        rest = unmatched;
+    }
+    if let Some(range) = rest {
+        accepted += accepted_in(machines, &machine.default, range);
+    }
+    accepted
+}
+
+fn accepted_combinations((machines, _): &System) -> usize {
+    accepted_in(machines, "in", PartRange::ALL)
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = System;
+
+    fn parse(input: &str) -> AocResult<Self::Input> {
+        parse(input)
+    }
+
+    fn warnings((machines, _): &Self::Input) -> Vec<String> {
+        validate(machines)
+            .iter()
+            .filter(|issue| !issue.is_fatal())
+            .map(Issue::to_string)
+            .collect()
+    }
+
+    fn exports((machines, _): &Self::Input) -> Vec<(&'static str, String)> {
+        vec![(DOT_FILE, to_dot(machines))]
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Answer {
+        accepted_rating(input).into()
+    }
+
+    fn part2(input: &Self::Input, _params: &Params) -> Answer {
+        accepted_combinations(input).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::error::Error;
 
-    use crate::get_input;
+    use std::time::Instant;
+
+    use crate::{answers::assert_answer, bench::Stats, get_input, solution};
 
     use super::*;
 
     #[test]
     fn day19_part1_test() {
-        let res = solve_part1(EXAMPLE);
+        let res = Day19::solve_part1(EXAMPLE).unwrap();
         println!("{res}");
         assert_eq!(res, 19114)
     }
@@ -204,28 +698,148 @@ mod tests {
     #[test]
     fn day19_part1() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 19)?;
-        let res = solve_part1(&input);
-        println!("day19 Part1 Result: {res}");
+        let res = Day19::solve_part1(&input).unwrap();
+        assert_answer(2023, 19, solution::Part::One, &res);
         Ok(())
     }
 
-    // #[test]
-    // fn day19_part2_test() {
-    //     let res = solve_part2(EXAMPLE);
-    //     assert_eq!(res, 62);
-    // }
-
-    // #[test]
-    // fn day19_part2_test() {
-    //     let res = solve_part2(EXAMPLE);
-    //     assert_eq!(res, 51);
-    // }
-
-    // #[test]
-    // fn day19_part2() -> Result<(), Box<dyn Error>> {
-    //     let input = get_input(2023, 19)?;
-    //     let res = solve_part2(&input);
-    //     println!("day19 Part2 Result: {res}");
-    //     Ok(())
-    // }
+    #[test]
+    fn rule_split() {
+        let rule = parse_rule("", "m>2090:A").unwrap();
+        let (matched, unmatched) = rule.split(PartRange::ALL);
+        assert_eq!(matched.unwrap().m, (2091, 4001));
+        assert_eq!(unmatched.unwrap().m, (1, 2091));
+        assert_eq!(matched.unwrap().x, (1, 4001));
+
+        let rule = parse_rule("", "a<1:R").unwrap();
+        assert_eq!(rule.split(PartRange::ALL), (None, Some(PartRange::ALL)));
+    }
+
+    #[test]
+    fn validation() {
+        let (machines, _) = parse(EXAMPLE).unwrap();
+        assert_eq!(validate(&machines), []);
+
+        let err = parse("in{a<5:px,R}\npx{x>10:A,foo}\n\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2: workflow 'px' sends parts to unknown workflow 'foo'"
+        );
+        let err = parse("in{a<5:px,R}\npx{x>10:A,qs}\nqs{m<3:R,px}\n\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 2: workflows loop: px -> qs -> px");
+
+        let system = parse("in{a<5:A,a<3:R,R}\nqs{a>4000:A,a<4001:R,A}\n\n").unwrap();
+        assert_eq!(
+            Day19::warnings(&system)[0],
+            "workflow 'qs' is never reached"
+        );
+        assert_eq!(
+            validate(&system.0),
+            [
+                Issue::Unreachable("qs".to_owned()),
+                Issue::DeadRule {
+                    workflow: "in".to_owned(),
+                    rule: 1
+                },
+                Issue::DeadRule {
+                    workflow: "qs".to_owned(),
+                    rule: 0
+                },
+                Issue::DeadRule {
+                    workflow: "qs".to_owned(),
+                    rule: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn dot_export() {
+        let (machines, _) = parse(EXAMPLE).unwrap();
+        let dot = to_dot(&machines);
+        assert!(dot.starts_with("digraph workflows {\n"));
+        assert!(dot.contains("    \"in\" -> \"px\" [label=\"s<1351\"];\n"));
+        assert!(dot.contains("    \"in\" -> \"qqz\";\n"));
+        assert_eq!(dot.matches(" -> ").count(), 25);
+
+        let system = Day19::parse(EXAMPLE).unwrap();
+        assert_eq!(Day19::exports(&system), [(DOT_FILE, dot)]);
+    }
+
+    #[test]
+    fn decision_tree() {
+        let (machines, parts) = parse(EXAMPLE).unwrap();
+        let tree = DecisionTree::compile(&machines);
+        // `gd` always rejects and `lnx` always accepts, which also leaves
+        // nothing for the rule in `qs` to decide
+        assert_eq!(tree.nodes.len(), 2 + 11);
+        let accepted = parts.iter().filter(|part| tree.accepts(part)).count();
+        assert_eq!(accepted, 3);
+
+        let system = (machines, generate_parts(10_000));
+        assert_eq!(accepted_rating(&system), interpreted_rating(&system));
+    }
+
+    // Pseudo random parts, the same ones on every run
+    fn generate_parts(count: usize) -> Vec<Part> {
+        let mut seed = 0x2545f4914f6cdd1du64;
+        let mut rating = move || {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            (seed % 4000) as usize + 1
+        };
+        (0..count)
+            .map(|_| Part {
+                x: rating(),
+                m: rating(),
+                a: rating(),
+                s: rating(),
+            })
+            .collect()
+    }
+
+    // `cargo test --release day19_compiled_vs_interpreted -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn day19_compiled_vs_interpreted() -> Result<(), Box<dyn Error>> {
+        let input = get_input(2023, 19)?;
+        let (machines, _) = parse(&input)?;
+        let system = (machines, generate_parts(2_000_000));
+
+        let mut results = Vec::new();
+        for (name, rate) in [
+            ("interpreted", interpreted_rating as fn(&System) -> usize),
+            ("compiled", accepted_rating),
+        ] {
+            let mut samples = Vec::new();
+            let mut rating = 0;
+            for _ in 0..5 {
+                let start = Instant::now();
+                rating = std::hint::black_box(rate(&system));
+                samples.push(start.elapsed());
+            }
+            let stats = Stats::from_samples(samples);
+            let per_second = system.1.len() as f64 / stats.median.as_secs_f64();
+            println!("{name}: median {:?}, {per_second:.0} parts/s", stats.median);
+            results.push(rating);
+        }
+        assert_eq!(results[0], results[1]);
+        Ok(())
+    }
+
+    #[test]
+    fn day19_part2_test() {
+        let res = Day19::solve_part2(EXAMPLE).unwrap();
+        assert_eq!(res, 167409079868000u64);
+    }
+
+    #[test]
+    #[ignore = "no accepted answer yet"]
+    fn day19_part2() -> Result<(), Box<dyn Error>> {
+        let input = get_input(2023, 19)?;
+        let res = Day19::solve_part2(&input).unwrap();
+        assert_answer(2023, 19, solution::Part::Two, &res);
+        Ok(())
+    }
 }
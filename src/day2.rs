@@ -1,5 +1,8 @@
 use std::{cmp::Ordering, error::Error, str::FromStr};
 
+use crate::error::{parse_in_line, parse_lines, AocError, AocResult};
+use crate::solution::{Answer, Param, ParamKind, Params, Solution};
+
 const EXAMPLE: &str = r#"Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
 Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
 Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
@@ -8,7 +11,7 @@ Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green
 "#;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
-struct CubeSet {
+pub struct CubeSet {
     red: i32,
     green: i32,
     blue: i32,
@@ -35,7 +38,7 @@ impl PartialOrd for CubeSet {
         let b_cmp = self.blue.cmp(&other.blue);
         match &[r_cmp, g_cmp, b_cmp] {
             [Ordering::Equal, Ordering::Equal, Ordering::Equal] => Some(Ordering::Equal),
-            ord if ord.iter().any(|ord| *ord == Ordering::Greater) => Some(Ordering::Greater),
+            ord if ord.contains(&Ordering::Greater) => Some(Ordering::Greater),
             ord if ord
                 .iter()
                 .all(|ord| *ord == Ordering::Less || *ord == Ordering::Equal) =>
@@ -47,34 +50,36 @@ impl PartialOrd for CubeSet {
     }
 }
 
-impl FromStr for CubeSet {
-    type Err = Box<dyn Error>;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut cubes_set = CubeSet {
-            red: 0,
-            green: 0,
-            blue: 0,
-        };
-        for cubes in s.split(", ") {
-            let mut split = cubes.split(" ");
-            let num = split
-                .next()
-                .ok_or("missing number of cubes")?
-                .parse::<i32>()?;
-
-            let color = split.next().ok_or("missing cube color")?;
-            match color {
-                "red" => cubes_set.red = num,
-                "green" => cubes_set.green = num,
-                "blue" => cubes_set.blue = num,
-                _ => {}
+// `s` is a slice of `line`, so errors can point at the offending token
+fn parse_cube_set(line: &str, s: &str) -> AocResult<CubeSet> {
+    let mut cubes_set = CubeSet {
+        red: 0,
+        green: 0,
+        blue: 0,
+    };
+    for cubes in s.split(", ") {
+        let (num, color) = cubes
+            .split_once(' ')
+            .ok_or_else(|| AocError::in_line(line, cubes, "expected '<number> <color>'"))?;
+        let num = parse_in_line(line, num)?;
+        match color {
+            "red" => cubes_set.red = num,
+            "green" => cubes_set.green = num,
+            "blue" => cubes_set.blue = num,
+            _ => {
+                return Err(AocError::in_line(
+                    line,
+                    color,
+                    format!("unknown color '{color}'"),
+                ))
             }
         }
-        Ok(cubes_set)
     }
+    Ok(cubes_set)
 }
 
-struct Game {
+#[derive(Debug)]
+pub struct Game {
     num: i32,
     cube_sets: Vec<CubeSet>,
 }
@@ -91,97 +96,117 @@ impl Game {
     }
 }
 
-impl FromStr for Game {
-    type Err = Box<dyn Error>;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut game = Game {
-            num: 0,
-            cube_sets: Vec::default(),
-        };
-        let mut colon_split = s.split(": ");
-        let game_info = colon_split.next().ok_or("missing game info")?;
-        let mut game_split = game_info.split(" ");
-        _ = game_split.next().ok_or("il formed game info")?;
-        game.num = game_split
-            .next()
-            .ok_or("missing game number")?
-            .parse::<i32>()?;
-
-        let cube_sets = colon_split.next().ok_or("missing cube sets after ':'")?;
-        for cube_set_str in cube_sets.split("; ") {
-            match CubeSet::from_str(cube_set_str) {
-                Ok(cs) => game.cube_sets.push(cs),
-                Err(e) => println!("{:?}", e),
-            };
-        }
-
-        Ok(game)
-    }
+fn parse_game(line: &str) -> AocResult<Game> {
+    let (game_info, cube_sets) = line.split_once(": ").ok_or("missing cube sets after ':'")?;
+    let num = game_info
+        .strip_prefix("Game ")
+        .ok_or_else(|| AocError::in_line(line, game_info, "expected 'Game <number>'"))?;
+
+    Ok(Game {
+        num: parse_in_line(line, num)?,
+        cube_sets: cube_sets
+            .split("; ")
+            .map(|cube_set| parse_cube_set(line, cube_set))
+            .collect::<AocResult<_>>()?,
+    })
 }
 
-fn solve_part1(input: &str, rules: CubeSet) -> i32 {
-    input
-        .lines()
-        .filter_map(|line| Game::from_str(line).ok())
-        .filter_map(|game| {
-            if game.is_valid(rules) {
-                Some(game.num)
-            } else {
-                None
-            }
-        })
+pub struct Day2;
+
+fn sum_valid_games(games: &[Game], rules: CubeSet) -> i32 {
+    games
+        .iter()
+        .filter(|game| game.is_valid(rules))
+        .map(|game| game.num)
         .sum()
 }
 
-fn solve_part2(input: &str) -> i32 {
-    input
-        .lines()
-        .filter_map(|line| Game::from_str(line).ok())
-        .map(|game| game.min_cube_set())
-        .map(|cube_set| cube_set.power())
-        .sum()
+impl Solution for Day2 {
+    type Input = Vec<Game>;
+
+    // The bag contents given in the puzzle text
+    const PARAMS: &'static [Param] = &[
+        Param {
+            name: "red",
+            default: 12,
+            kind: ParamKind::U32,
+        },
+        Param {
+            name: "green",
+            default: 13,
+            kind: ParamKind::U32,
+        },
+        Param {
+            name: "blue",
+            default: 14,
+            kind: ParamKind::U32,
+        },
+    ];
+
+    fn parse(input: &str) -> AocResult<Self::Input> {
+        parse_lines(input, parse_game)
+    }
+
+    fn part1(input: &Self::Input, params: &Params) -> Answer {
+        let bag = CubeSet {
+            red: params.get("red"),
+            green: params.get("green"),
+            blue: params.get("blue"),
+        };
+        sum_valid_games(input, bag).into()
+    }
+
+    fn part2(input: &Self::Input, _params: &Params) -> Answer {
+        input
+            .iter()
+            .map(|game| game.min_cube_set())
+            .map(|cube_set| cube_set.power())
+            .sum::<i32>()
+            .into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::get_input;
+    use crate::{answers::assert_answer, get_input, solution::Part};
 
     use super::*;
     #[test]
     fn day2_part1_test() {
-        let rules = CubeSet {
-            red: 12,
-            green: 13,
-            blue: 14,
-        };
-
-        let res = solve_part1(EXAMPLE, rules);
+        let res = Day2::solve_part1(EXAMPLE).unwrap();
         assert_eq!(res, 8);
+
+        let params = Day2::params().with("red", 20).with("blue", 15);
+        let res = Day2::solve_part1_with(EXAMPLE, &params).unwrap();
+        assert_eq!(res, 15);
     }
 
     #[test]
     fn day2_part1() -> Result<(), Box<dyn Error>> {
-        let rules = CubeSet {
-            red: 12,
-            green: 13,
-            blue: 14,
-        };
         let input = get_input(2023, 2)?;
-        let res = solve_part1(&input, rules);
-        println!("Day2 Part1 Result: {res}");
+        let res = Day2::solve_part1(&input).unwrap();
+        assert_answer(2023, 2, Part::One, &res);
         Ok(())
     }
+    #[test]
+    fn day2_parse_errors() {
+        let err = Day2::parse("Game 1: 3 blue\nGame 2: 1 blue, 2 purple\n").unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 19: unknown color 'purple'");
+        let err = Day2::parse("Game x: 3 blue").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(1), Some(6)));
+    }
+
     #[test]
     fn day2_part2_test() {
-        let res = solve_part2(EXAMPLE);
+        let res = Day2::solve_part2(EXAMPLE).unwrap();
         assert_eq!(res, 2286);
     }
 
     #[test]
     fn day2_part2() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 2)?;
-        let res = solve_part2(&input);
-        println!("Day2 Part2 Result: {res}");
+        let res = Day2::solve_part2(&input).unwrap();
+        assert_answer(2023, 2, Part::Two, &res);
         Ok(())
     }
 }
@@ -1,8 +1,8 @@
-use std::{
-    collections::{BinaryHeap, HashMap, HashSet},
-    fmt::Write,
-    str::FromStr,
-};
+use crate::error::AocResult;
+use crate::geometry::{Direction, Point};
+use crate::grid::Grid;
+use crate::search;
+use crate::solution::{Answer, Param, ParamKind, Params, Solution};
 
 const EXAMPLE: &str = r#"...........
 .....###.#.
@@ -16,163 +16,189 @@ const EXAMPLE: &str = r#"...........
 .##..##.##.
 ..........."#;
 
-#[derive(Debug, Default, Clone)]
-struct Grid {
-    height: usize,
-    width: usize,
-    data: Vec<u8>,
+type Pos = Point<usize>;
+
+fn find_start(grid: &Grid<u8>) -> Pos {
+    grid.find(|&tile| tile == b'S')
+        .expect("checked by Day21::parse")
 }
 
-impl Grid {
-    fn get(&self, x: usize, y: usize) -> u8 {
-        if x > (self.width - 1) || y > (self.height - 1) {
-            return 0;
-        }
+// Anywhere on the garden repeated endlessly in every direction
+type InfinitePos = Point<i64>;
 
-        let i = y * self.width + x;
-        self.data[i]
-    }
+fn tile_at(grid: &Grid<u8>, pos: InfinitePos) -> u8 {
+    let x = pos.x.rem_euclid(grid.width() as i64) as usize;
+    let y = pos.y.rem_euclid(grid.height() as i64) as usize;
+    grid[Point::new(x, y)]
+}
 
-    fn set(&mut self, x: usize, y: usize, val: u8) {
-        let i = y * self.width + x;
-        if let Some(data) = self.data.get_mut(i) {
-            *data = val;
-        }
+// The plot counts for every number of steps, from a single walk out of the
+// start. A plot can be reached in exactly `steps` if it is at most that far
+// away and the steps left over can be spent walking back and forth.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Garden {
+    // Plots reachable in exactly as many steps as the index
+    series: Vec<usize>,
+    // Whether every plot was reached, so the counts stop changing
+    complete: bool,
+}
+
+impl Garden {
+    fn bounded(grid: &Grid<u8>) -> Self {
+        let reach = search::bfs_reach([find_start(grid)], usize::MAX, |&pos| {
+            grid.neighbours4(pos).filter(|&next| grid[next] != b'#')
+        });
+        Garden::from_distances(reach.distances.into_values(), None)
     }
 
-    fn find_start(&self) -> Pos {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                if self.get(x, y) == b'S' {
-                    return (x, y);
-                }
-            }
-        }
-        (0, 0)
+    // Only feasible for a few hundred steps, the number of plots grows with
+    // the square of `max_steps`
+    fn infinite(grid: &Grid<u8>, max_steps: usize) -> Self {
+        let start = find_start(grid);
+        let start = Point::new(start.x as i64, start.y as i64);
+        let reach = search::bfs_reach([start], max_steps, |&pos: &InfinitePos| {
+            Direction::ALL
+                .into_iter()
+                .filter_map(move |dir| pos.step(dir))
+                .filter(|&next| tile_at(grid, next) != b'#')
+        });
+        Garden::from_distances(reach.distances.into_values(), Some(max_steps))
     }
-}
 
-impl FromStr for Grid {
-    type Err = ();
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let mut grid = Grid::default();
-        let mut width = 0;
-        for line in input.lines() {
-            width = width.max(line.len());
-            grid.data.extend(line.as_bytes().iter().copied());
-            grid.height += 1;
+    // Without `max_steps` every plot has to be in `distances`
+    fn from_distances(distances: impl Iterator<Item = usize>, max_steps: Option<usize>) -> Self {
+        let distances = distances.collect::<Vec<_>>();
+        let farthest = distances.iter().copied().max().unwrap_or(0);
+        let mut series = vec![0; max_steps.unwrap_or(farthest) + 1];
+        for distance in distances {
+            series[distance] += 1;
+        }
+        for steps in 2..series.len() {
+            series[steps] += series[steps - 2];
+        }
+        Garden {
+            series,
+            complete: max_steps.is_none(),
         }
-        grid.width = width;
-        Ok(grid)
     }
-}
 
-impl std::fmt::Display for Grid {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("W: {} H: {}\n", self.width, self.height))?;
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let data = self.get(x, y);
-                f.write_char(data as char)?;
-            }
+    // Plot counts from 0 steps up to the farthest plot, or `max_steps`
+    fn series(&self) -> &[usize] {
+        &self.series
+    }
 
-            f.write_char('\n')?;
+    // `None` past the steps an unfinished walk got to
+    fn reachable(&self, steps: usize) -> Option<usize> {
+        let last = self.series.len() - 1;
+        if steps <= last {
+            return Some(self.series[steps]);
         }
-        Ok(())
+        if !self.complete {
+            return None;
+        }
+        // Past the farthest plot only the parity matters
+        Some(match last {
+            _ if (steps - last).is_multiple_of(2) => self.series[last],
+            0 => 0,
+            _ => self.series[last - 1],
+        })
     }
 }
 
-type Pos = (usize, usize);
-
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Plot {
-    step: usize,
-    pos: Pos,
+// Square with the start in the middle of a clear row and column, like the
+// real inputs. Walking straight out then reaches every garden copy in the
+// fewest possible steps.
+fn has_open_cross(grid: &Grid<u8>) -> bool {
+    let start = find_start(grid);
+    let middle = grid.width() / 2;
+    grid.width() == grid.height()
+        && start == Point::new(middle, middle)
+        && grid.row(middle).iter().all(|&tile| tile != b'#')
+        && grid.column(middle).all(|&tile| tile != b'#')
 }
 
-impl<'a> PartialOrd for Plot {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.step.cmp(&other.step))
-    }
-}
+// Gardens without an open cross are walked step by step, which is only
+// feasible up to here
+const MAX_WALKED_STEPS: usize = 1000;
 
-impl<'a> Ord for Plot {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(&other).unwrap()
+// With an open cross the reached diamond grows by another ring of gardens
+// every `size` steps, so the plot count grows quadratically in the number of
+// rings. Three walked counts with the same remainder fix the quadratic, which
+// is then evaluated at the ring `steps` ends in. Counts that do not grow like
+// that, or a result too large for a `usize`, give `None`.
+fn extrapolated_plots(steps: usize, grid: &Grid<u8>) -> Option<usize> {
+    let size = grid.width();
+    let rings = steps / size;
+    let open_cross = has_open_cross(grid);
+    if !open_cross && steps > MAX_WALKED_STEPS {
+        return None;
     }
-}
-
-fn solve_part1(steps: usize, input: &str) -> u32 {
-    let mut grid = Grid::from_str(input).unwrap();
-    println!("{grid}");
-    let start = grid.find_start();
-    let mut stack = BinaryHeap::new();
-
-    for (x, y) in [(-1, 0), (0, -1), (1, 0), (0, 1)] {
-        let next_pos = ((start.0 as i32 + x) as usize, (start.1 as i32 + y) as usize);
-        stack.push(Plot {
-            step: steps - 1,
-            pos: next_pos,
-        });
+    if !open_cross || rings < 3 {
+        return Garden::infinite(grid, steps).reachable(steps);
     }
-    let mut current_step = steps - 1;
 
-    let mut odd_plots = 0;
-    let mut even_plots = 1;
-    while let Some(Plot { step, pos }) = stack.pop() {
-        if step < current_step {
-            //println!("{step}: {grid}");
-            current_step = step;
-        }
-
-        let tile = grid.get(pos.0, pos.1);
-
-        //println!("[{step}] {pos:?} : {}", tile as char);
+    let remainder = steps % size;
+    let garden = Garden::infinite(grid, remainder + 2 * size);
+    let [a, b, c] = [0, 1, 2].map(|ring| garden.reachable(remainder + ring * size));
+    let (a, b, c) = (a?, b?, c?);
+    // Newton's forward differences
+    let first = b.checked_sub(a)?;
+    let second = c.checked_add(a)?.checked_sub(b.checked_mul(2)?)?;
+    let pairs = rings.checked_mul(rings - 1)? / 2;
+    a.checked_add(first.checked_mul(rings)?)?
+        .checked_add(second.checked_mul(pairs)?)
+}
 
-        if tile != b'.' {
-            continue;
-        }
+pub struct Day21;
 
-        if step % 2 == 0 {
-            even_plots += 1;
-        } else {
-            odd_plots += 1;
-        }
+impl Solution for Day21 {
+    type Input = Grid<u8>;
 
-        grid.set(pos.0, pos.1, b'O');
-        if step == 0 {
-            continue;
-        }
+    const PARAMS: &'static [Param] = &[
+        Param {
+            name: "steps",
+            default: 64,
+            kind: ParamKind::Usize,
+        },
+        Param {
+            name: "infinite_steps",
+            default: 26501365,
+            kind: ParamKind::Usize,
+        },
+    ];
 
-        for (x, y) in [(-1, 0), (0, -1), (1, 0), (0, 1)] {
-            let next_pos = ((pos.0 as i32 + x) as usize, (pos.1 as i32 + y) as usize);
-            stack.push(Plot {
-                step: step - 1,
-                pos: next_pos,
-            });
+    fn parse(input: &str) -> AocResult<Self::Input> {
+        let grid = Grid::parse(input, b".#S")?;
+        if grid.find(|&tile| tile == b'S').is_none() {
+            return Err("missing start 'S'".into());
         }
+        Ok(grid)
     }
 
-    println!("0: {grid}");
-    println!("{odd_plots}, {even_plots}");
-    even_plots
-}
+    fn part1(input: &Self::Input, params: &Params) -> Answer {
+        Garden::bounded(input)
+            .reachable(params.get("steps"))
+            .map_or(Answer::Unsolved, Answer::from)
+    }
 
-fn solve_part2(input: &str) -> u32 {
-    0
+    // Only gardens like the real inputs can be extrapolated that far
+    fn part2(input: &Self::Input, params: &Params) -> Answer {
+        extrapolated_plots(params.get("infinite_steps"), input)
+            .map_or(Answer::Unsolved, Answer::from)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::error::Error;
 
-    use crate::get_input;
+    use crate::{answers::assert_answer, get_input, solution::Part};
 
     use super::*;
 
     #[test]
     fn day21_part1_test() {
-        let res = solve_part1(6, EXAMPLE);
+        let res = Day21::solve_part1_with(EXAMPLE, &Day21::params().with("steps", 6)).unwrap();
         println!("{res}");
         assert_eq!(res, 16)
     }
@@ -180,22 +206,73 @@ mod tests {
     #[test]
     fn day21_part1() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 21)?;
-        let res = solve_part1(64, &input);
-        println!("day21 Part1 Result: {res}");
+        let res = Day21::solve_part1(&input).unwrap();
+        assert_answer(2023, 21, Part::One, &res);
         Ok(())
     }
 
-    // #[test]
-    // fn day21_part2_test() {
-    //     let res = solve_part2(EXAMPLE);
-    //     assert_eq!(res, 51);
-    // }
+    #[test]
+    fn garden_series() {
+        let grid = Day21::parse(EXAMPLE).unwrap();
+        let garden = Garden::bounded(&grid);
+        assert_eq!(garden.series()[..7], [1, 2, 4, 6, 9, 13, 16]);
+        assert_eq!(garden.reachable(6), Some(16));
+        // Every plot is within reach long before 1000 steps
+        let plots = grid.iter().filter(|(_, &tile)| tile != b'#').count();
+        let [even, odd] = [1000, 1001].map(|steps| garden.reachable(steps).unwrap());
+        assert_eq!(even + odd, plots);
+
+        let infinite = Garden::infinite(&grid, 100);
+        assert_eq!(infinite.series()[..7], garden.series()[..7]);
+        assert_eq!(infinite.reachable(50), Some(1594));
+        assert_eq!(infinite.reachable(101), None);
+    }
+
+    #[test]
+    fn day21_part2_test() {
+        for (steps, plots) in [(6, 16), (10, 50), (50, 1594), (100, 6536)] {
+            let params = Day21::params().with("infinite_steps", steps);
+            let res = Day21::solve_part2_with(EXAMPLE, &params).unwrap();
+            assert_eq!(res, plots, "after {steps} steps");
+        }
+    }
+
+    #[test]
+    fn day21_part2_needs_open_cross() {
+        let grid = Day21::parse("...\n#S#\n...").unwrap();
+        assert!(!has_open_cross(&grid));
+        assert_eq!(extrapolated_plots(10, &grid), Some(89));
+        assert_eq!(extrapolated_plots(MAX_WALKED_STEPS + 1, &grid), None);
+        let res = Day21::solve_part2("...\n#S#\n...").unwrap();
+        assert_eq!(res, Answer::Unsolved);
+    }
+
+    #[test]
+    fn day21_part2_overflow() {
+        let grid = Day21::parse("...\n.S.\n...").unwrap();
+        assert!(has_open_cross(&grid));
+        assert_eq!(extrapolated_plots(10, &grid), Some(121));
+        assert_eq!(extrapolated_plots(usize::MAX, &grid), None);
+    }
+
+    #[test]
+    fn day21_part2_extrapolation() -> Result<(), Box<dyn Error>> {
+        let grid = Day21::parse(&get_input(2023, 21)?)?;
+        let steps = grid.width() / 2 + 3 * grid.width();
+        assert_eq!(
+            extrapolated_plots(steps, &grid),
+            Garden::infinite(&grid, steps).reachable(steps)
+        );
+        assert!(extrapolated_plots(steps, &grid).is_some());
+        Ok(())
+    }
 
-    // #[test]
-    // fn day21_part2() -> Result<(), Box<dyn Error>> {
-    //     let input = get_input(2023, 21)?;
-    //     let res = solve_part2(&input);
-    //     println!("day21 Part2 Result: {res}");
-    //     Ok(())
-    // }
+    #[test]
+    #[ignore = "no accepted answer yet"]
+    fn day21_part2() -> Result<(), Box<dyn Error>> {
+        let input = get_input(2023, 21)?;
+        let res = Day21::solve_part2(&input).unwrap();
+        assert_answer(2023, 21, Part::Two, &res);
+        Ok(())
+    }
 }
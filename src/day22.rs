@@ -1,9 +1,9 @@
-use std::{
-    collections::{BinaryHeap, HashMap, HashSet},
-    error::Error,
-    fmt::Write,
-    str::FromStr,
-};
+use std::fmt::Write;
+
+use crate::error::{parse_in_line, parse_lines, AocError, AocResult};
+use crate::geometry::Point;
+use crate::grid::Grid;
+use crate::solution::{Answer, Params, Solution};
 
 const EXAMPLE: &str = r#"1,0,1~1,2,1
 0,0,2~2,0,2
@@ -16,193 +16,339 @@ const EXAMPLE: &str = r#"1,0,1~1,2,1
 type Pos = (isize, isize, isize);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Brick {
+pub struct Brick {
     start: Pos,
     end: Pos,
 }
 
 impl Brick {
-    fn overlap_xy(&self, other: &Brick) -> bool {
-        if other.start.0 > self.end.0
-            || other.start.1 > self.end.1
-            || other.end.0 < self.start.0
-            || other.end.1 < self.start.1
-        {
-            false
-        } else {
-            true
-        }
-    }
     fn check_format(&self) -> bool {
         self.start.0 <= self.end.0 && self.start.1 <= self.end.1 && self.start.2 <= self.end.2
     }
 }
 
-impl FromStr for Brick {
-    type Err = Box<dyn Error>;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split('~');
-        let mut start_nums = parts.next().ok_or(format!("Bad format"))?.split(',');
-        let mut end_nums = parts.next().ok_or(format!("Bad format"))?.split(',');
-        Ok(Brick {
-            start: (
-                start_nums.next().ok_or(format!("Bad format"))?.parse()?,
-                start_nums.next().ok_or(format!("Bad format"))?.parse()?,
-                start_nums.next().ok_or(format!("Bad format"))?.parse()?,
-            ),
-            end: (
-                end_nums.next().ok_or(format!("Bad format"))?.parse()?,
-                end_nums.next().ok_or(format!("Bad format"))?.parse()?,
-                end_nums.next().ok_or(format!("Bad format"))?.parse()?,
-            ),
-        })
+fn parse_pos(line: &str, pos: &str) -> AocResult<Pos> {
+    let nums = pos
+        .split(',')
+        .map(|n| parse_in_line(line, n))
+        .collect::<AocResult<Vec<_>>>()?;
+    match nums[..] {
+        [x, y, z] => Ok((x, y, z)),
+        _ => Err(AocError::in_line(line, pos, "expected 'x,y,z'")),
     }
 }
 
-fn solve_part1(input: &str) -> u32 {
-    let mut bricks = input
-        .lines()
-        .filter_map(|l| match Brick::from_str(l) {
-            Ok(b) => Some(b),
-            Err(e) => {
-                println!("Could not parse brick from {l}: {e}");
-                None
-            }
-        })
-        .collect::<Vec<_>>();
-
-    if !bricks.iter().all(Brick::check_format) {
-        panic!("Not all bricks have the correct format!!");
+// `1,0,1~1,2,1`, the start can't be past the end on any axis
+fn parse_brick(line: &str) -> AocResult<Brick> {
+    let (start, end) = line
+        .split_once('~')
+        .ok_or_else(|| AocError::in_line(line, line, "expected '<start>~<end>'"))?;
+    let brick = Brick {
+        start: parse_pos(line, start)?,
+        end: parse_pos(line, end)?,
+    };
+    if !brick.check_format() {
+        return Err(AocError::in_line(line, end, "end lies before the start"));
     }
+    Ok(brick)
+}
 
-    for i in 0..bricks.len() {
-        let mut brick = bricks[i];
-        let length = brick.end.2 - brick.start.2;
-        let start_z = brick.start.2;
-        for lower_brick in bricks[..i].iter().rev() {
-            if brick.overlap_xy(lower_brick) {
-                //print!("[{i}] {brick:?} overlaps {lower_brick:?} ");
-                let lower_z = lower_brick.end.2;
-                if brick.start.2 != start_z {
-                    if lower_z > brick.start.2 + 1 {
-                        brick.start.2 = lower_z + 1;
-                        brick.end.2 = brick.start.2 + length;
-                        //println!("moved to z {}", lower_z + 1);
-                    } else {
-                        //println!("");
+// The bricks after they all came to rest, together with which ones hold up
+// which. Both support lists are indexed like `bricks`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BrickStack {
+    bricks: Vec<Brick>,
+    supports: Vec<Vec<usize>>,
+    supported_by: Vec<Vec<usize>>,
+}
+
+impl BrickStack {
+    // Drops the bricks lowest first. The height map holds the top of the
+    // stack for every x/y column together with the brick that is on top
+    // there, so each brick only has to look at the columns below it.
+    fn settle(bricks: &[Brick]) -> Self {
+        let min_x = bricks.iter().map(|b| b.start.0).min().unwrap_or(0);
+        let min_y = bricks.iter().map(|b| b.start.1).min().unwrap_or(0);
+        let max_x = bricks.iter().map(|b| b.end.0).max().unwrap_or(0);
+        let max_y = bricks.iter().map(|b| b.end.1).max().unwrap_or(0);
+        let width = (max_x - min_x + 1) as usize;
+        let depth = (max_y - min_y + 1) as usize;
+        let mut heights = Grid::filled(width, depth, (0, None::<usize>));
+
+        let mut order = (0..bricks.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&i| bricks[i].start.2);
+
+        let mut settled = bricks.to_vec();
+        let mut supports = vec![Vec::new(); bricks.len()];
+        let mut supported_by = vec![Vec::new(); bricks.len()];
+        for i in order {
+            let brick = bricks[i];
+            let columns = (brick.start.0..=brick.end.0)
+                .flat_map(|x| (brick.start.1..=brick.end.1).map(move |y| (x, y)))
+                .map(|(x, y)| Point::new((x - min_x) as usize, (y - min_y) as usize))
+                .collect::<Vec<_>>();
+
+            let rest_on = columns
+                .iter()
+                .map(|&column| heights[column].0)
+                .max()
+                .unwrap_or(0);
+            for &column in &columns {
+                if let (top, Some(below)) = heights[column] {
+                    if top == rest_on && !supported_by[i].contains(&below) {
+                        supported_by[i].push(below);
+                        supports[below].push(i);
                     }
-                } else if lower_z + 1 < brick.start.2 {
-                    brick.start.2 = lower_z + 1;
-                    brick.end.2 = brick.start.2 + length;
-                    //println!("moved to z {}", lower_z + 1);
-                } else {
-                    //println!("");
                 }
             }
+
+            let fall = brick.start.2 - (rest_on + 1);
+            settled[i].start.2 -= fall;
+            settled[i].end.2 -= fall;
+            for &column in &columns {
+                heights[column] = (settled[i].end.2, Some(i));
+            }
         }
 
-        bricks[i] = brick;
+        BrickStack {
+            bricks: settled,
+            supports,
+            supported_by,
+        }
     }
 
-    let mut supports = HashMap::new();
-    let mut supported_by = HashMap::new();
+    // Everything it holds up rests on another brick as well
+    fn is_safe(&self, brick: usize) -> bool {
+        self.supports[brick]
+            .iter()
+            .all(|&above| self.supported_by[above].len() > 1)
+    }
 
-    for (i, brick) in bricks.iter().enumerate() {
-        for (j, other_brick) in bricks[i + 1..].iter().enumerate() {
-            let j = j + i + 1;
-            if brick.overlap_xy(other_brick) {
-                let this_top_z = brick.end.2;
-                let other_bottom_z = other_brick.start.2;
-
-                let this_bottom_z = brick.start.2;
-                let other_top_z = other_brick.end.2;
-
-                if this_top_z + 1 == other_bottom_z {
-                    let sup = supported_by.entry(j).or_insert(Vec::default());
-                    if !sup.contains(&i) {
-                        sup.push(i);
-                    }
-                    let sup = supports.entry(i).or_insert(Vec::default());
-                    if !sup.contains(&j) {
-                        sup.push(j);
-                    }
-                } else if other_top_z + 1 == this_bottom_z {
-                    let sup = supported_by.entry(i).or_insert(Vec::default());
-                    if !sup.contains(&j) {
-                        sup.push(j);
-                    }
-                    let sup = supports.entry(j).or_insert(Vec::default());
-                    if !sup.contains(&i) {
-                        sup.push(i);
-                    }
-                }
-            }
-        }
+    fn safe_to_disintegrate(&self) -> usize {
+        (0..self.bricks.len())
+            .filter(|&brick| self.is_safe(brick))
+            .count()
     }
 
-    let mut can_be_removed = HashSet::new();
+    // How many other bricks fall when each one is disintegrated.
+    //
+    // A brick falls exactly when every way down to the ground goes through
+    // the disintegrated one, i.e. when that one dominates it in the support
+    // graph rooted at the ground. Going upwards, the immediate dominator of a
+    // brick is the closest common dominator of all bricks it rests on, and
+    // the bricks that fall are the ones in its dominator subtree.
+    fn chain_reactions(&self) -> Vec<usize> {
+        let ground = self.bricks.len();
+        let mut order = (0..self.bricks.len()).collect::<Vec<_>>();
+        order.sort_by_key(|&brick| self.bricks[brick].start.2);
 
-    for (supported, supporters) in supported_by.iter() {
-        //println!("{supported:?} supported by {supporters:?}");
-        if supporters.len() > 1 {
-            for s in supporters {
-                can_be_removed.insert(*s);
-            }
+        let mut dominator = vec![ground; self.bricks.len() + 1];
+        let mut depth = vec![0; self.bricks.len() + 1];
+        for &brick in &order {
+            let mut below = self.supported_by[brick].iter().copied();
+            let idom = match below.next() {
+                None => ground,
+                Some(first) => below.fold(first, |a, b| common_dominator(&dominator, &depth, a, b)),
+            };
+            dominator[brick] = idom;
+            depth[brick] = depth[idom] + 1;
         }
 
-        if !supports.contains_key(supported) {
-            can_be_removed.insert(*supported);
+        let mut subtree = vec![1; self.bricks.len() + 1];
+        for &brick in order.iter().rev() {
+            subtree[dominator[brick]] += subtree[brick];
         }
+        subtree.truncate(self.bricks.len());
+        subtree.iter().map(|size| size - 1).collect()
     }
+}
 
-    for (_, supporters) in supported_by.iter() {
-        //println!("{supported:?} supported by {supporters:?}");
-        if supporters.len() == 1 {
-            let _ = can_be_removed.remove(&supporters[0]);
+fn common_dominator(dominator: &[usize], depth: &[usize], mut a: usize, mut b: usize) -> usize {
+    while a != b {
+        if depth[a] >= depth[b] {
+            a = dominator[a];
+        } else {
+            b = dominator[b];
         }
     }
+    a
+}
+
+const SAFE_COLOUR: [u8; 3] = [80, 200, 80];
+const LOAD_BEARING_COLOUR: [u8; 3] = [220, 60, 50];
+
+// Corner `i` of a box is offset by bit 0 in x, bit 1 in y and bit 2 in z.
+// The faces wind counter-clockwise seen from outside.
+const BOX_FACES: [[usize; 4]; 6] = [
+    [0, 2, 3, 1],
+    [4, 5, 7, 6],
+    [0, 1, 5, 4],
+    [2, 6, 7, 3],
+    [0, 4, 6, 2],
+    [1, 3, 7, 5],
+];
 
-    can_be_removed.len() as u32
+// An ASCII PLY mesh with one box per brick, each one covering the cubes from
+// its start to its end
+fn to_ply(bricks: &[Brick], colour: impl Fn(usize) -> [u8; 3]) -> String {
+    let mut out = String::new();
+    writeln!(out, "ply").unwrap();
+    writeln!(out, "format ascii 1.0").unwrap();
+    writeln!(out, "element vertex {}", bricks.len() * 8).unwrap();
+    for axis in ["x", "y", "z"] {
+        writeln!(out, "property float {axis}").unwrap();
+    }
+    for channel in ["red", "green", "blue"] {
+        writeln!(out, "property uchar {channel}").unwrap();
+    }
+    writeln!(out, "element face {}", bricks.len() * BOX_FACES.len()).unwrap();
+    writeln!(out, "property list uchar int vertex_indices").unwrap();
+    writeln!(out, "end_header").unwrap();
+
+    for (i, brick) in bricks.iter().enumerate() {
+        let [r, g, b] = colour(i);
+        for corner in 0..8 {
+            let pick = |bit: usize, start: isize, end: isize| {
+                if corner & bit == 0 {
+                    start
+                } else {
+                    end + 1
+                }
+            };
+            let x = pick(1, brick.start.0, brick.end.0);
+            let y = pick(2, brick.start.1, brick.end.1);
+            let z = pick(4, brick.start.2, brick.end.2);
+            writeln!(out, "{x} {y} {z} {r} {g} {b}").unwrap();
+        }
+    }
+    for i in 0..bricks.len() {
+        for face in BOX_FACES {
+            let [a, b, c, d] = face.map(|corner| i * 8 + corner);
+            writeln!(out, "4 {a} {b} {c} {d}").unwrap();
+        }
+    }
+    out
 }
 
-fn solve_part2(input: &str) -> u32 {
-    0
+// The bricks as they are in the input and after settling, coloured by
+// whether they are safe to disintegrate
+fn export_ply(bricks: &[Brick]) -> Vec<(&'static str, String)> {
+    let stack = BrickStack::settle(bricks);
+    let colour = |i| {
+        if stack.is_safe(i) {
+            SAFE_COLOUR
+        } else {
+            LOAD_BEARING_COLOUR
+        }
+    };
+    vec![
+        (PLY_FALLING, to_ply(bricks, colour)),
+        (PLY_SETTLED, to_ply(&stack.bricks, colour)),
+    ]
+}
+
+const PLY_FALLING: &str = "day22_falling.ply";
+const PLY_SETTLED: &str = "day22_settled.ply";
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = Vec<Brick>;
+
+    fn parse(input: &str) -> AocResult<Self::Input> {
+        parse_lines(input, parse_brick)
+    }
+
+    fn exports(input: &Self::Input) -> Vec<(&'static str, String)> {
+        export_ply(input)
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Answer {
+        BrickStack::settle(input).safe_to_disintegrate().into()
+    }
+
+    fn part2(input: &Self::Input, _params: &Params) -> Answer {
+        let falling: usize = BrickStack::settle(input).chain_reactions().iter().sum();
+        falling.into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::error::Error;
 
-    use crate::get_input;
+    use crate::{answers::assert_answer, get_input, solution::Part};
 
     use super::*;
 
     #[test]
     fn day22_part1_test() {
-        let res = solve_part1(EXAMPLE);
+        let res = Day22::solve_part1(EXAMPLE).unwrap();
         println!("{res}");
-        //assert_eq!(res, 16)
+        assert_eq!(res, 5)
     }
 
     #[test]
+    fn settling_ignores_line_order() {
+        let shuffled = EXAMPLE.lines().rev().collect::<Vec<_>>().join("\n");
+        assert_eq!(Day22::solve_part1(&shuffled).unwrap(), 5);
+        assert_eq!(Day22::solve_part2(&shuffled).unwrap(), 7);
+
+        let stack = BrickStack::settle(&Day22::parse(EXAMPLE).unwrap());
+        let bottoms = stack.bricks.iter().map(|b| b.start.2).collect::<Vec<_>>();
+        assert_eq!(bottoms, [1, 2, 2, 3, 3, 4, 5]);
+    }
+
+    #[test]
+    fn ply_export() {
+        let bricks = Day22::parse("1,0,1~1,2,1\n0,0,2~2,0,2").unwrap();
+        let ply = to_ply(&bricks, |i| [i as u8; 3]);
+        let (header, body) = ply.split_once("end_header\n").unwrap();
+        assert!(header.contains("element vertex 16\n"));
+        assert!(header.contains("element face 12\n"));
+
+        let lines = body.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 16 + 12);
+        // The first brick spans x 1..2, y 0..3 and z 1..2
+        assert_eq!(lines[0], "1 0 1 0 0 0");
+        assert_eq!(lines[7], "2 3 2 0 0 0");
+        assert_eq!(lines[8], "0 0 2 1 1 1");
+        assert_eq!(lines[16], "4 0 2 3 1");
+        assert_eq!(lines[27], "4 9 11 15 13");
+
+        let exports = Day22::exports(&Day22::parse(EXAMPLE).unwrap());
+        let names = exports.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+        assert_eq!(names, [PLY_FALLING, PLY_SETTLED]);
+        assert_ne!(exports[0].1, exports[1].1);
+    }
+
+    #[test]
+    #[ignore = "no accepted answer yet"]
     fn day22_part1() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 22)?;
-        let res = solve_part1(&input);
-        println!("day22 Part1 Result: {res}");
+        let res = Day22::solve_part1(&input).unwrap();
+        assert_answer(2023, 22, Part::One, &res);
         Ok(())
     }
 
-    // #[test]
-    // fn day22_part2_test() {
-    //     let res = solve_part2(EXAMPLE);
-    //     assert_eq!(res, 51);
-    // }
+    #[test]
+    fn chain_reactions() {
+        let stack = BrickStack::settle(&Day22::parse(EXAMPLE).unwrap());
+        assert_eq!(stack.chain_reactions(), [6, 0, 0, 0, 0, 1, 0]);
+    }
+
+    #[test]
+    fn day22_part2_test() {
+        let res = Day22::solve_part2(EXAMPLE).unwrap();
+        assert_eq!(res, 7);
+    }
 
-    // #[test]
-    // fn day22_part2() -> Result<(), Box<dyn Error>> {
-    //     let input = get_input(2023, 22)?;
-    //     let res = solve_part2(&input);
-    //     println!("day22 Part2 Result: {res}");
-    //     Ok(())
-    // }
+    #[test]
+    #[ignore = "no accepted answer yet"]
+    fn day22_part2() -> Result<(), Box<dyn Error>> {
+        let input = get_input(2023, 22)?;
+        let res = Day22::solve_part2(&input).unwrap();
+        assert_answer(2023, 22, Part::Two, &res);
+        Ok(())
+    }
 }
@@ -1,8 +1,12 @@
-use std::{
-    collections::{BinaryHeap, HashMap, HashSet},
-    fmt::Write,
-    str::FromStr,
-};
+use std::collections::HashMap;
+
+use rayon::prelude::*;
+
+use crate::error::{AocError, AocResult};
+use crate::geometry::{Direction, Point};
+use crate::grid::{Grid, Pos};
+use crate::search;
+use crate::solution::{Answer, Params, Solution};
 
 const EXAMPLE: &str = r#"#.#####################
 #.......#########...###
@@ -28,168 +32,347 @@ const EXAMPLE: &str = r#"#.#####################
 #.....###...###...#...#
 #####################.#"#;
 
-#[derive(Debug, Default, Clone)]
-struct Grid {
-    height: usize,
-    width: usize,
-    data: Vec<u8>,
+// How the `^`, `>`, `v` and `<` slopes can be walked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SlopePolicy {
+    // Only down, in the direction of the arrow
+    OneWay,
+    // Just like any other path
+    Climbable,
+    // Both ways, but every step up a slope counts twice
+    UphillCostsDouble,
 }
 
-impl Grid {
-    fn get(&self, x: usize, y: usize) -> u8 {
-        if x > (self.width - 1) || y > (self.height - 1) {
-            return 0;
+impl SlopePolicy {
+    // What stepping onto `tile` in `dir` adds to the hike, if it is possible
+    fn step_cost(self, tile: u8, dir: Direction) -> Option<u32> {
+        if tile == b'#' {
+            return None;
+        }
+        let uphill = Direction::from_arrow(tile as char) == Some(dir.reverse());
+        match (self, uphill) {
+            (_, false) | (SlopePolicy::Climbable, true) => Some(1),
+            (SlopePolicy::OneWay, true) => None,
+            (SlopePolicy::UphillCostsDouble, true) => Some(2),
         }
-
-        let i = y * self.width + x;
-        self.data[i]
     }
 
-    fn set(&mut self, x: usize, y: usize, val: u8) {
-        let i = y * self.width + x;
-        if let Some(data) = self.data.get_mut(i) {
-            *data = val;
-        }
+    // The tiles a hiker can step to from `pos`, with what each step costs
+    fn moves(self, grid: &Grid<u8>, pos: Pos) -> impl Iterator<Item = (Pos, u32)> + '_ {
+        Direction::ALL.into_iter().filter_map(move |dir| {
+            let next_pos = grid.step(pos, dir)?;
+            Some((next_pos, self.step_cost(grid[next_pos], dir)?))
+        })
     }
 }
 
-impl FromStr for Grid {
-    type Err = ();
-    fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let mut grid = Grid::default();
-        let mut width = 0;
-        for line in input.lines() {
-            width = width.max(line.len());
-            grid.data.extend(line.as_bytes().iter().copied());
-            grid.height += 1;
-        }
-        grid.width = width;
-        Ok(grid)
-    }
+// The trails start at the path tile in the top row and end at the one in the
+// bottom row
+fn trail_start(grid: &Grid<u8>) -> Option<Pos> {
+    open_tile(grid, 0)
 }
 
-impl std::fmt::Display for Grid {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("W: {} H: {}\n", self.width, self.height))?;
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let data = self.get(x, y);
-                f.write_char(data as char)?;
-            }
+fn trail_end(grid: &Grid<u8>) -> Option<Pos> {
+    open_tile(grid, grid.height() - 1)
+}
 
-            f.write_char('\n')?;
-        }
-        Ok(())
+fn open_tile(grid: &Grid<u8>, y: usize) -> Option<Pos> {
+    let x = grid.row(y).iter().position(|&tile| tile != b'#')?;
+    Some(Point::new(x, y))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Hike {
+    length: u32,
+    // Every tile from the start to the end
+    path: Vec<Pos>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Corridor {
+    to: usize,
+    length: u32,
+    // The tiles after the junction it leaves, up to and including `to`
+    tiles: Vec<Pos>,
+}
+
+// The trails with every corridor collapsed into a single weighted edge
+// between junctions, the tiles where a hiker has a choice to make. The start
+// and end count as junctions too.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct TrailGraph {
+    junctions: Vec<Pos>,
+    // The corridors leaving each junction
+    edges: Vec<Vec<Corridor>>,
+    start: usize,
+    end: usize,
+    // The only junction that leads to the end, if there is just one
+    before_end: Option<usize>,
+}
+
+// The junctions a hike went through, one bit per junction
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Visited(Vec<u64>);
+
+impl Visited {
+    fn new(junctions: usize) -> Self {
+        Visited(vec![0; junctions.div_ceil(64)])
+    }
+
+    fn contains(&self, junction: usize) -> bool {
+        self.0[junction / 64] & 1 << (junction % 64) != 0
+    }
+
+    fn insert(&mut self, junction: usize) {
+        self.0[junction / 64] |= 1 << (junction % 64);
+    }
+
+    fn remove(&mut self, junction: usize) {
+        self.0[junction / 64] &= !(1 << (junction % 64));
     }
 }
 
-type Dir = (isize, isize);
-const UP: Dir = (0, -1);
-const DOWN: Dir = (0, 1);
-const RIGHT: Dir = (1, 0);
-const LEFT: Dir = (-1, 0);
-const NONE: Dir = (0, 0);
-
-fn solve_part1(input: &str) -> u32 {
-    let grid = Grid::from_str(input).unwrap();
-    println!("{grid}");
-    let mut stack = Vec::new();
-    stack.push(((1, 0), DOWN, 0, ));
-    let mut longest_path = 0;
-    let end = (grid.width - 2, grid.height -1);
-    while let Some((pos, dir, step)) = stack.pop() {
-        if pos == end {
-            longest_path = longest_path.max(step);
-        }
+// How many junctions deep the search fans out before the branches are
+// handed to rayon
+const PARALLEL_DEPTH: usize = 6;
 
-        for next_dir in [(-1, 0), (0, -1), (1, 0), (0, 1)] {
-            let next_pos = ((pos.0 as isize + next_dir.0) as usize, (pos.1 as isize + next_dir.1) as usize);
-            match (dir, next_dir) {
-                (UP, DOWN) | (DOWN, UP) | (RIGHT, LEFT) | (LEFT, RIGHT) => continue,
-                _ => {}
-            }
+// The longest hike found so far and the junctions on the way to the current
+// one, shared by a single depth first search
+struct HikeSearch {
+    route: Vec<usize>,
+    visited: Visited,
+    best: Option<(u32, Vec<usize>)>,
+}
 
-            let tile = grid.get(next_pos.0, next_pos.1);
-            match (tile, next_dir) {
-                (b'>', LEFT) | (b'<', RIGHT) | (b'^', DOWN) | (b'v', UP) | (b'#', _) | (0, _) => {
-                    continue
+impl TrailGraph {
+    fn new(grid: &Grid<u8>, policy: SlopePolicy) -> Option<Self> {
+        TrailGraph::compress(grid, |pos| policy.moves(grid, pos))
+    }
+
+    // `moves` gives the tiles a hiker can step to from a tile, with what
+    // each step costs. Without a start or end there are no trails.
+    fn compress<I>(grid: &Grid<u8>, moves: impl Fn(Pos) -> I) -> Option<Self>
+    where
+        I: IntoIterator<Item = (Pos, u32)>,
+    {
+        let (start, end) = (trail_start(grid)?, trail_end(grid)?);
+        let exits = |pos| {
+            grid.neighbours4(pos)
+                .filter(|&next| grid[next] != b'#')
+                .count()
+        };
+        let junctions = grid
+            .positions()
+            .filter(|&pos| grid[pos] != b'#')
+            .filter(|&pos| pos == start || pos == end || exits(pos) >= 3)
+            .collect::<Vec<_>>();
+        let ids = junctions
+            .iter()
+            .enumerate()
+            .map(|(id, &pos)| (pos, id))
+            .collect::<HashMap<_, _>>();
+
+        let mut edges = vec![Vec::new(); junctions.len()];
+        for (from, &junction) in junctions.iter().enumerate() {
+            for (first, cost) in moves(junction) {
+                let (mut previous, mut pos, mut length) = (junction, first, cost);
+                let mut tiles = vec![first];
+                // Corridors that end in a dead end or run up a slope are dropped
+                loop {
+                    if let Some(&to) = ids.get(&pos) {
+                        if to != from {
+                            edges[from].push(Corridor { to, length, tiles });
+                        }
+                        break;
+                    }
+                    let next = moves(pos).into_iter().find(|&(next, _)| next != previous);
+                    let Some((next, cost)) = next else {
+                        break;
+                    };
+                    tiles.push(next);
+                    (previous, pos, length) = (pos, next, length + cost);
                 }
-                _ => {}
             }
+        }
+
+        let end = ids[&end];
+        let mut leading_to_end = (0..junctions.len())
+            .filter(|&from| edges[from].iter().any(|corridor| corridor.to == end));
+        let before_end = match (leading_to_end.next(), leading_to_end.next()) {
+            (Some(from), None) => Some(from),
+            _ => None,
+        };
 
-            stack.push((next_pos, next_dir, step + 1));
+        Some(TrailGraph {
+            junctions,
+            edges,
+            start: ids[&start],
+            end,
+            before_end,
+        })
+    }
+
+    fn longest_hike(&self) -> Option<Hike> {
+        let mut visited = Visited::new(self.junctions.len());
+        visited.insert(self.start);
+        let mut branches = vec![(vec![self.start], visited, 0)];
+        for _ in 0..PARALLEL_DEPTH {
+            branches = branches
+                .into_iter()
+                .flat_map(|(route, visited, length)| {
+                    let node = *route.last().unwrap();
+                    if node == self.end {
+                        return vec![(route, visited, length)];
+                    }
+                    self.unvisited(node, &visited)
+                        .map(|corridor| {
+                            let mut route = route.clone();
+                            route.push(corridor.to);
+                            let mut visited = visited.clone();
+                            visited.insert(corridor.to);
+                            (route, visited, length + corridor.length)
+                        })
+                        .collect()
+                })
+                .collect();
         }
+
+        let (length, route) = branches
+            .into_par_iter()
+            .filter_map(|(route, visited, length)| {
+                let mut search = HikeSearch {
+                    route,
+                    visited,
+                    best: None,
+                };
+                self.longest_from(&mut search, length);
+                search.best
+            })
+            .max_by_key(|(length, _)| *length)?;
+        Some(Hike {
+            length,
+            path: self.expand(&route),
+        })
     }
 
-    longest_path
-}
+    fn unvisited<'a>(
+        &'a self,
+        node: usize,
+        visited: &'a Visited,
+    ) -> impl Iterator<Item = &'a Corridor> {
+        self.edges[node]
+            .iter()
+            .filter(|corridor| !visited.contains(corridor.to))
+    }
 
-fn solve_part2(input: &str) -> u32 {
-    let grid = Grid::from_str(input).unwrap();
-    println!("{grid}");
-    let mut stack = Vec::new();
-    let mut next_poses = Vec::new();
-    stack.push(((1, 0), DOWN, 0, HashSet::new()));
-    let mut longest_path = 0;
-    let end = (grid.width - 2, grid.height -1);
-    while let Some((pos, dir, step, mut visited)) = stack.pop() {
-        if pos == end {
-            longest_path = longest_path.max(step);
-            continue
+    fn longest_from(&self, search: &mut HikeSearch, length: u32) {
+        let node = *search.route.last().unwrap();
+        if node == self.end {
+            if search.best.as_ref().is_none_or(|(best, _)| length > *best) {
+                search.best = Some((length, search.route.clone()));
+            }
+            return;
         }
-
-        
-        visited.insert(pos);
-        next_poses.clear();
-        for next_dir in [(-1, 0), (0, -1), (1, 0), (0, 1)] {
-            let next_pos = ((pos.0 as isize + next_dir.0) as usize, (pos.1 as isize + next_dir.1) as usize);
-            match (dir, next_dir) {
-                (UP, DOWN) | (DOWN, UP) | (RIGHT, LEFT) | (LEFT, RIGHT) => continue,
-                _ => {}
+        for corridor in &self.edges[node] {
+            if search.visited.contains(corridor.to) {
+                continue;
             }
-
-            if visited.contains(&next_pos) {
+            // Passing by the end would leave no way back to it
+            if Some(node) == self.before_end && corridor.to != self.end {
                 continue;
             }
+            search.route.push(corridor.to);
+            search.visited.insert(corridor.to);
+            self.longest_from(search, length + corridor.length);
+            search.visited.remove(corridor.to);
+            search.route.pop();
+        }
+    }
 
-            let tile = grid.get(next_pos.0, next_pos.1);
-            match (tile, next_dir) {
-                (b'#', _) | (0, _) => {
-                    continue
-                }
-                _ => {}
-            }
-            next_poses.push((next_pos, next_dir));
+    // The tiles walked along a route of junctions
+    fn expand(&self, route: &[usize]) -> Vec<Pos> {
+        let mut path = vec![self.junctions[route[0]]];
+        for step in route.windows(2) {
+            let corridor = self.edges[step[0]]
+                .iter()
+                .filter(|corridor| corridor.to == step[1])
+                .max_by_key(|corridor| corridor.length)
+                .expect("routes only follow corridors");
+            path.extend(&corridor.tiles);
         }
+        path
+    }
+}
 
-        if next_poses.len() == 1 {
-            let (next_pos, next_dir) = next_poses.last().unwrap();
-            stack.push((*next_pos, *next_dir, step + 1, visited));
-        } else {
-            for (i, (next_pos, next_dir)) in next_poses.iter().enumerate() {
-                if i == next_poses.len() - 1  {
-                    stack.push((*next_pos, *next_dir, step + 1, visited));
-                    break;
-                } else {
-                    stack.push((*next_pos, *next_dir, step + 1, visited.clone()));
-                } 
-            }
+fn longest_hike(grid: &Grid<u8>, policy: SlopePolicy) -> Option<Hike> {
+    TrailGraph::new(grid, policy)?.longest_hike()
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = Grid<u8>;
+
+    fn parse(input: &str) -> AocResult<Self::Input> {
+        let grid = Grid::parse(input, b".#<>^v")?.non_empty()?;
+        if trail_start(&grid).is_none() {
+            return Err(AocError::at(
+                1,
+                1,
+                "no path tile to start on in the top row",
+            ));
+        }
+        if trail_end(&grid).is_none() {
+            return Err(AocError::at(
+                grid.height(),
+                1,
+                "no path tile to end on in the bottom row",
+            ));
         }
+        Ok(grid)
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Answer {
+        longest_hike(input, SlopePolicy::OneWay).map_or(Answer::Unsolved, |hike| hike.length.into())
+    }
+
+    fn part2(input: &Self::Input, _params: &Params) -> Answer {
+        longest_hike(input, SlopePolicy::Climbable)
+            .map_or(Answer::Unsolved, |hike| hike.length.into())
     }
-    longest_path
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
     use std::error::Error;
 
-    use crate::get_input;
+    use crate::{answers::assert_answer, get_input, solution::Part};
 
     use super::*;
 
+    #[test]
+    fn day23_parse_errors() {
+        assert!(Day23::parse("").is_err());
+        let err = Day23::parse("#").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 1, column 1: no path tile to start on in the top row"
+        );
+        let err = Day23::parse("#.#\n#.#\n###").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 1: no path tile to end on in the bottom row"
+        );
+        // The start and end are wherever the path meets the edge
+        let grid = Day23::parse("..#\n#.#\n#..").unwrap();
+        assert_eq!(trail_start(&grid), Some(Point::new(0, 0)));
+        assert_eq!(trail_end(&grid), Some(Point::new(1, 2)));
+    }
+
     #[test]
     fn day23_part1_test() {
-        let res = solve_part1(EXAMPLE);
+        let res = Day23::solve_part1(EXAMPLE).unwrap();
         println!("{res}");
         assert_eq!(res, 94)
     }
@@ -197,22 +380,112 @@ mod tests {
     #[test]
     fn day23_part1() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 23)?;
-        let res = solve_part1(&input);
-        println!("day23 Part1 Result: {res}");
+        let res = Day23::solve_part1(&input).unwrap();
+        assert_answer(2023, 23, Part::One, &res);
         Ok(())
     }
 
+    #[test]
+    fn trail_graph() {
+        let grid = Day23::parse(EXAMPLE).unwrap();
+        let graph = TrailGraph::new(&grid, SlopePolicy::Climbable).unwrap();
+        assert_eq!(graph.junctions.len(), 9);
+        assert_eq!(graph.junctions[graph.start], Point::new(1, 0));
+        let [ref corridor] = graph.edges[graph.start][..] else {
+            panic!("the start should lead to a single junction");
+        };
+        assert_eq!(graph.junctions[corridor.to], Point::new(3, 5));
+        assert_eq!((corridor.length, corridor.tiles.len()), (15, 15));
+        // Every corridor can be walked both ways
+        let edges = graph.edges.iter().map(Vec::len).sum::<usize>();
+        assert_eq!(edges, 2 * 12);
+        assert_eq!(graph.longest_hike().map(|hike| hike.length), Some(154));
+    }
+
+    #[test]
+    fn hike_path() {
+        let grid = Day23::parse(EXAMPLE).unwrap();
+        for (policy, length) in [(SlopePolicy::OneWay, 94), (SlopePolicy::Climbable, 154)] {
+            let hike = longest_hike(&grid, policy).unwrap();
+            assert_eq!(hike.length, length);
+            assert_eq!(hike.path.len() as u32, length + 1);
+            assert_eq!(hike.path[0], Point::new(1, 0));
+            assert_eq!(hike.path[hike.path.len() - 1], Point::new(21, 22));
+            assert!(hike
+                .path
+                .windows(2)
+                .all(|step| step[0].manhattan(step[1]) == 1));
+            assert_eq!(
+                hike.path.iter().collect::<HashSet<_>>().len(),
+                hike.path.len()
+            );
+        }
+    }
+
+    // A corridor with a dead end branching off every other tile, more
+    // junctions than fit into a single word of visited bits
+    #[test]
+    fn many_junctions() {
+        let width = 2 * 64 + 6;
+        let row = |open: &dyn Fn(usize) -> bool| {
+            (0..width)
+                .map(|x| if open(x) { '.' } else { '#' })
+                .collect::<String>()
+        };
+        let input = [
+            row(&|x| x == 1),
+            row(&|x| x > 0 && x < width - 1),
+            row(&|x| x > 0 && x < width - 2 && x % 2 == 0 || x == width - 2),
+            row(&|x| x == width - 2),
+        ]
+        .join("\n");
+        let grid = Day23::parse(&input).unwrap();
+        let graph = TrailGraph::new(&grid, SlopePolicy::Climbable).unwrap();
+        assert!(graph.junctions.len() > 64);
+        let hike = longest_hike(&grid, SlopePolicy::Climbable).unwrap();
+        assert_eq!(hike.length as usize, width);
+        assert_eq!(hike.path.len(), width + 1);
+        assert_eq!(Day23::solve_part1(&input).unwrap(), width as u64);
+    }
+
+    #[test]
+    fn uphill_costs_double() {
+        let grid = Day23::parse(EXAMPLE).unwrap();
+        let hike = longest_hike(&grid, SlopePolicy::UphillCostsDouble).unwrap();
+        let steps = |&pos: &Pos| SlopePolicy::UphillCostsDouble.moves(&grid, pos);
+        let end = Point::new(21, 22);
+        let tiles = search::longest_path(Point::new(1, 0), steps, |&pos| pos == end).unwrap();
+        assert_eq!(hike.length, tiles.cost);
+        assert!(hike.length > 154);
+        // The path walks the same tiles as the length counts
+        let cost = hike
+            .path
+            .windows(2)
+            .map(|step| {
+                let dir = Direction::ALL
+                    .into_iter()
+                    .find(|&dir| grid.step(step[0], dir) == Some(step[1]))
+                    .unwrap();
+                SlopePolicy::UphillCostsDouble
+                    .step_cost(grid[step[1]], dir)
+                    .unwrap()
+            })
+            .sum::<u32>();
+        assert_eq!(cost, hike.length);
+    }
+
     #[test]
     fn day23_part2_test() {
-        let res = solve_part2(EXAMPLE);
+        let res = Day23::solve_part2(EXAMPLE).unwrap();
         assert_eq!(res, 154);
     }
 
     #[test]
+    #[ignore = "no accepted answer yet"]
     fn day23_part2() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 23)?;
-        let res = solve_part2(&input);
-        println!("day23 Part2 Result: {res}");
+        let res = Day23::solve_part2(&input).unwrap();
+        assert_answer(2023, 23, Part::Two, &res);
         Ok(())
     }
 }
@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
-use crate::day1;
+use crate::error::{AocError, AocResult};
+use crate::solution::{Answer, Params, Solution};
 
 const EXAMPLE: &str = r#"467..114..
 ...*......
@@ -29,26 +30,43 @@ fn to_coords(pos: usize, width: usize) -> (usize, usize) {
     (x, y)
 }
 
+#[derive(Clone)]
 struct Symbol {
     sym: u8,
     visits: usize,
     gear_ratio: i32,
 }
 
-fn parse(input: &str) -> (Vec<Number>, HashMap<(usize, usize), Symbol>, usize) {
-    let first_line = input
-        .lines()
-        .next()
-        .expect("missing line in input")
-        .as_bytes();
+pub struct Day3;
+
+pub struct Schematic {
+    numbers: Vec<Number>,
+    symbols: HashMap<(usize, usize), Symbol>,
+    width: usize,
+}
+
+type Parsed = (Vec<Number>, HashMap<(usize, usize), Symbol>, usize);
+
+fn parse(input: &str) -> AocResult<Parsed> {
+    let first_line = input.lines().next().ok_or("missing line in input")?;
     let width = first_line.len();
+    // Positions are derived from the byte offset, so every row must be equally wide
+    for (row, line) in input.lines().enumerate() {
+        if line.len() != width {
+            return Err(AocError::at(
+                row + 1,
+                line.len().min(width) + 1,
+                format!("expected {width} columns, found {}", line.len()),
+            ));
+        }
+    }
 
     let mut symbols = HashMap::new();
     let mut tokens = Vec::default();
     let mut pending_num_token: Option<Number> = None;
     let mut i = 0;
     for byte in input.bytes() {
-        if b'0' <= byte && byte <= b'9' {
+        if byte.is_ascii_digit() {
             let num = (byte - b'0') as i32;
 
             if let Some(num_token) = &mut pending_num_token {
@@ -79,7 +97,7 @@ fn parse(input: &str) -> (Vec<Number>, HashMap<(usize, usize), Symbol>, usize) {
             i += 1;
         }
     }
-    (tokens, symbols, width)
+    Ok((tokens, symbols, width))
 }
 
 fn get_num_width(num: i32) -> usize {
@@ -125,17 +143,6 @@ fn valid_number(num: Number, symbols: &HashMap<(usize, usize), Symbol>, width: u
     false
 }
 
-fn solve_part1(input: &str) -> i32 {
-    let (numbers, symbols, width) = parse(input);
-    //println!("{:?}", numbers);
-    //println!("{:?}", symbols);
-    numbers
-        .iter()
-        .filter(|n| valid_number(**n, &symbols, width))
-        .map(|n| n.number)
-        .sum()
-}
-
 fn gear_ratio(num: Number, symbols: &mut HashMap<(usize, usize), Symbol>, width: usize) {
     let num_width = get_num_width(num.number);
     let pos = num.pos;
@@ -175,23 +182,48 @@ fn gear_ratio(num: Number, symbols: &mut HashMap<(usize, usize), Symbol>, width:
     }
 }
 
-fn solve_part2(input: &str) -> i32 {
-    let (numbers, mut symbols, width) = parse(input);
-    numbers
-        .iter()
-        .for_each(|n| gear_ratio(*n, &mut symbols, width));
-    symbols
-        .iter()
-        .filter(|(_, s)| s.sym == b'*' && s.visits == 2)
-        .map(|(_, s)| s.gear_ratio)
-        .sum()
+impl Solution for Day3 {
+    type Input = Schematic;
+
+    fn parse(input: &str) -> AocResult<Self::Input> {
+        let (numbers, symbols, width) = parse(input)?;
+        Ok(Schematic {
+            numbers,
+            symbols,
+            width,
+        })
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Answer {
+        input
+            .numbers
+            .iter()
+            .filter(|n| valid_number(**n, &input.symbols, input.width))
+            .map(|n| n.number)
+            .sum::<i32>()
+            .into()
+    }
+
+    fn part2(input: &Self::Input, _params: &Params) -> Answer {
+        let mut symbols = input.symbols.clone();
+        input
+            .numbers
+            .iter()
+            .for_each(|n| gear_ratio(*n, &mut symbols, input.width));
+        symbols
+            .iter()
+            .filter(|(_, s)| s.sym == b'*' && s.visits == 2)
+            .map(|(_, s)| s.gear_ratio)
+            .sum::<i32>()
+            .into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::error::Error;
 
-    use crate::get_input;
+    use crate::{answers::assert_answer, get_input, solution::Part};
 
     use super::*;
 
@@ -204,28 +236,28 @@ mod tests {
 
     #[test]
     fn day3_part1_test() {
-        let res = solve_part1(EXAMPLE);
+        let res = Day3::solve_part1(EXAMPLE).unwrap();
         assert_eq!(res, 4361);
     }
 
     #[test]
     fn day3_part1() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 3)?;
-        let res = solve_part1(&input);
-        println!("Day3 Part1 Result: {res}");
+        let res = Day3::solve_part1(&input).unwrap();
+        assert_answer(2023, 3, Part::One, &res);
         Ok(())
     }
     #[test]
     fn day3_part2_test() {
-        let res = solve_part2(EXAMPLE);
+        let res = Day3::solve_part2(EXAMPLE).unwrap();
         assert_eq!(res, 467835);
     }
 
     #[test]
     fn day3_part2() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 3)?;
-        let res = solve_part2(&input);
-        println!("Day3 Part2 Result: {res}");
+        let res = Day3::solve_part2(&input).unwrap();
+        assert_answer(2023, 3, Part::Two, &res);
         Ok(())
     }
 }
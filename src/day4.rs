@@ -1,5 +1,8 @@
 use std::{error::Error, num, str::FromStr};
 
+use crate::error::{parse_in_line, parse_lines, AocError, AocResult};
+use crate::solution::{Answer, Params, Solution};
+
 const EXAMPLE: &str = r#"Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53
 Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19
 Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1
@@ -8,7 +11,7 @@ Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36
 Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11
 "#;
 
-struct Card {
+pub struct Card {
     id: i32,
     winning_numbers: Vec<i32>,
     numbers: Vec<i32>,
@@ -35,122 +38,101 @@ impl Card {
     }
 }
 
-impl FromStr for Card {
-    type Err = Box<dyn Error>;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut card = Card {
-            id: 0,
-            winning_numbers: Vec::default(),
-            numbers: Vec::default(),
-        };
-
-        let mut card_split = s.split(": ");
-        let card_id = card_split
-            .next()
-            .ok_or("bad format")?
-            .strip_prefix("Card ")
-            .ok_or("bad format")?;
-
-        match card_id.parse::<i32>() {
-            Ok(id) => card.id = id,
-            Err(_) => {
-                format!("Could not parse to int: '{}'", card_id);
-            }
-        }
-
-        let nums = card_split.next().ok_or("missing number info")?;
-        let mut num_split = nums.split(" | ");
-        let winning_numbers_split = num_split
-            .next()
-            .ok_or("missing winning numbers")?
-            .split(" ");
-        card.winning_numbers = winning_numbers_split
-            .map(str::parse::<i32>)
-            .filter_map(|res| res.ok())
-            .collect();
-        let numbers_split = num_split.next().ok_or("missing numbers")?.split(" ");
-        card.numbers = numbers_split
-            .map(str::parse::<i32>)
-            .filter_map(|res| res.ok())
-            .collect();
-        Ok(card)
-    }
+fn parse_numbers(line: &str, numbers: &str) -> AocResult<Vec<i32>> {
+    numbers
+        .split_whitespace()
+        .map(|num| parse_in_line(line, num))
+        .collect()
 }
 
-fn solve_part1(input: &str) -> i32 {
-    input
-        .lines()
-        .map(Card::from_str)
-        .filter_map(|c| match c {
-            Ok(c) => Some(c),
-            Err(e) => {
-                println!("{:?}", e);
-                None
-            }
-        })
-        .map(|c| c.value())
-        .sum()
+fn parse_card(line: &str) -> AocResult<Card> {
+    let (card_id, nums) = line.split_once(": ").ok_or("missing number info")?;
+    let card_id = card_id
+        .strip_prefix("Card")
+        .ok_or_else(|| AocError::in_line(line, card_id, "expected 'Card <number>'"))?
+        .trim_start();
+    let (winning_numbers, numbers) = nums
+        .split_once(" | ")
+        .ok_or_else(|| AocError::in_line(line, nums, "missing ' | ' between the numbers"))?;
+
+    Ok(Card {
+        id: parse_in_line(line, card_id)?,
+        winning_numbers: parse_numbers(line, winning_numbers)?,
+        numbers: parse_numbers(line, numbers)?,
+    })
 }
 
-fn solve_part2(input: &str) -> u32 {
-    let mut card_matches = input
-        .lines()
-        .map(Card::from_str)
-        .filter_map(|c| c.ok())
-        .map(|c| (c.num_matches(), 1u32))
-        .collect::<Vec<_>>();
-
-    let mut num_cards = 0;
-    for i in 0..card_matches.len() {
-        let (matches, instances) = card_matches[i];
-        if matches > 0 {
-            for _ in 1..=instances {
-                for next in 1..=matches {
-                    let next_card = i + (next as usize);
-                    if next_card < card_matches.len() {
-                        card_matches[next_card].1 += 1;
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<Card>;
+
+    fn parse(input: &str) -> AocResult<Self::Input> {
+        parse_lines(input, parse_card)
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Answer {
+        input.iter().map(|c| c.value()).sum::<i32>().into()
+    }
+
+    fn part2(input: &Self::Input, _params: &Params) -> Answer {
+        let mut card_matches = input
+            .iter()
+            .map(|c| (c.num_matches(), 1u32))
+            .collect::<Vec<_>>();
+
+        let mut num_cards = 0;
+        for i in 0..card_matches.len() {
+            let (matches, instances) = card_matches[i];
+            if matches > 0 {
+                for _ in 1..=instances {
+                    for next in 1..=matches {
+                        let next_card = i + (next as usize);
+                        if next_card < card_matches.len() {
+                            card_matches[next_card].1 += 1;
+                        }
                     }
                 }
             }
+            num_cards += instances;
         }
-        num_cards += instances;
-    }
 
-    num_cards
+        num_cards.into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::error::Error;
 
-    use crate::get_input;
+    use crate::{answers::assert_answer, get_input, solution::Part};
 
     use super::*;
 
     #[test]
     fn day4_part1_test() {
-        let res = solve_part1(EXAMPLE);
+        let res = Day4::solve_part1(EXAMPLE).unwrap();
         assert_eq!(res, 13);
     }
 
     #[test]
     fn day4_part1() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 4)?;
-        let res = solve_part1(&input);
-        println!("Day4 Part1 Result: {res}");
+        let res = Day4::solve_part1(&input).unwrap();
+        assert_answer(2023, 4, Part::One, &res);
         Ok(())
     }
     #[test]
     fn day4_part2_test() {
-        let res = solve_part2(EXAMPLE);
+        let res = Day4::solve_part2(EXAMPLE).unwrap();
         assert_eq!(res, 30);
     }
 
     #[test]
     fn day4_part2() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 4)?;
-        let res = solve_part2(&input);
-        println!("Day4 Part2 Result: {res}");
+        let res = Day4::solve_part2(&input).unwrap();
+        assert_answer(2023, 4, Part::Two, &res);
         Ok(())
     }
 }
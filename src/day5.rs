@@ -1,5 +1,8 @@
 use std::{collections::HashSet, error::Error, num, str::FromStr};
 
+use crate::error::{parse_in_line, AocError, AocResult};
+use crate::solution::{Answer, Params, Solution};
+
 const EXAMPLE: &str = r#"seeds: 79 14 55 13
 
 seed-to-soil map:
@@ -62,29 +65,31 @@ impl Range {
     }
 }
 
-impl FromStr for Range {
-    type Err = ();
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let nums = s
-            .split(" ")
-            .filter_map(|n| n.parse::<u64>().ok())
-            .collect::<Vec<_>>();
-
-        if nums.len() != 3 {
-            //println!("Range::from_str: '{s}' parsed to {:?}", nums);
-            return Err(());
-        }
+fn parse_numbers(line: &str, numbers: &str) -> AocResult<Vec<u64>> {
+    numbers
+        .split_whitespace()
+        .map(|n| parse_in_line(line, n))
+        .collect()
+}
 
-        Ok(Range {
-            input_start: nums[1],
-            output_start: nums[0],
-            length: nums[2],
-        })
-    }
+fn parse_range(line: &str) -> AocResult<Range> {
+    let nums = parse_numbers(line, line)?;
+    let [output_start, input_start, length] = nums[..] else {
+        return Err(AocError::new(format!(
+            "expected 3 numbers in a range, found {}",
+            nums.len()
+        )));
+    };
+
+    Ok(Range {
+        input_start,
+        output_start,
+        length,
+    })
 }
 
-#[derive(Debug, Default)]
-struct Map {
+#[derive(Debug, Default, Clone)]
+pub struct Map {
     name: String,
     ranges: Vec<Range>,
 }
@@ -124,63 +129,64 @@ impl Map {
     }
 }
 
-fn parse(input: &str) -> (Vec<u64>, Vec<Map>) {
-    let mut lines = input.lines();
+// `header` is the 0-based line the map was declared on
+fn finish_map(header: usize, mut map: Map) -> AocResult<Map> {
+    if map.ranges.is_empty() {
+        return Err(AocError::new(format!("map '{}' has no ranges", map.name)).on_line(header + 1));
+    }
+    map.ranges.sort_by_key(|e1| e1.input_start);
+    Ok(map)
+}
 
-    let seeds = lines
-        .next()
-        .unwrap()
-        .split(": ")
-        .nth(1)
-        .unwrap()
-        .split(" ")
-        .filter_map(|n| n.parse::<u64>().ok())
-        .collect::<Vec<_>>();
+fn parse(input: &str) -> AocResult<(Vec<u64>, Vec<Map>)> {
+    let mut lines = input.lines().enumerate();
 
-    lines.next().unwrap(); // skip first empty line..
+    let (_, first_line) = lines.next().ok_or("missing seeds")?;
+    let seeds = first_line
+        .strip_prefix("seeds:")
+        .ok_or_else(|| AocError::at(1, 1, "expected 'seeds:'"))?;
+    let seeds = parse_numbers(first_line, seeds).map_err(|e| e.on_line(1))?;
 
-    let mut current_map: Option<Map> = None;
+    let mut current_map: Option<(usize, Map)> = None;
     let mut all_maps = Vec::default();
 
-    for line in lines {
-        if let Some(mut map) = current_map {
-            if line.is_empty() {
-                map.ranges
-                    .sort_by(|e1, e2| e1.input_start.cmp(&e2.input_start));
-                all_maps.push(map);
-                current_map = None;
-            } else {
-                map.ranges.push(Range::from_str(line).unwrap());
-                current_map = Some(map);
+    for (i, line) in lines {
+        if line.is_empty() {
+            if let Some((header, map)) = current_map.take() {
+                all_maps.push(finish_map(header, map)?);
             }
+        } else if let Some((_, map)) = &mut current_map {
+            map.ranges
+                .push(parse_range(line).map_err(|e| e.on_line(i + 1))?);
         } else {
-            let mut new_map = Map::default();
-            new_map.name = line.split(" map:").next().unwrap().to_owned();
-            current_map = Some(new_map);
+            let name = line
+                .strip_suffix(" map:")
+                .ok_or_else(|| AocError::at(i + 1, 1, "expected '<name> map:'"))?;
+            current_map = Some((
+                i,
+                Map {
+                    name: name.to_owned(),
+                    ..Default::default()
+                },
+            ));
         }
     }
 
-    if let Some(mut map) = current_map {
-        map.ranges
-            .sort_by(|e1, e2| e1.input_start.cmp(&e2.input_start));
-        all_maps.push(map);
+    if let Some((header, map)) = current_map {
+        all_maps.push(finish_map(header, map)?);
     }
 
-    // for m in &all_maps {
-    //     println!("{:?}", m);
-    // }
-
-    (seeds, all_maps)
+    Ok((seeds, all_maps))
 }
 
-fn solve_part1(input: &str) -> u64 {
-    let (seeds, all_maps) = parse(input);
+pub struct Day5;
 
+fn lowest_location(seeds: &[u64], all_maps: &[Map]) -> u64 {
     seeds
         .iter()
         .map(|seed| {
             let mut v = *seed;
-            for map in &all_maps {
+            for map in all_maps {
                 // print!("[{seed}] ");
                 v = map.mapped(v)
             }
@@ -190,15 +196,11 @@ fn solve_part1(input: &str) -> u64 {
         .unwrap()
 }
 
-fn solve_part2(input: &str) -> u64 {
-    let (seeds, mut all_maps) = parse(input);
+fn lowest_location_of_ranges(seeds: &[u64], mut all_maps: Vec<Map>) -> u64 {
+    all_maps
+        .iter_mut()
+        .for_each(|map| map.ranges.sort_by_key(|e1| e1.output_start));
 
-    all_maps.iter_mut().for_each(|map| {
-        map.ranges
-            .sort_by(|e1, e2| e1.output_start.cmp(&e2.output_start))
-    });
-
-    println!("Input Seeds: {:?}", seeds);
     let mut ranges = Vec::new();
     let mut max = 0;
     for v in seeds.chunks(2) {
@@ -223,39 +225,55 @@ fn solve_part2(input: &str) -> u64 {
     0
 }
 
+impl Solution for Day5 {
+    type Input = (Vec<u64>, Vec<Map>);
+
+    fn parse(input: &str) -> AocResult<Self::Input> {
+        parse(input)
+    }
+
+    fn part1((seeds, all_maps): &Self::Input, _params: &Params) -> Answer {
+        lowest_location(seeds, all_maps).into()
+    }
+
+    fn part2((seeds, all_maps): &Self::Input, _params: &Params) -> Answer {
+        lowest_location_of_ranges(seeds, all_maps.clone()).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::error::Error;
 
-    use crate::get_input;
+    use crate::{answers::assert_answer, get_input, solution::Part};
 
     use super::*;
 
     #[test]
     fn day5_part1_test() {
-        let res = solve_part1(EXAMPLE);
+        let res = Day5::solve_part1(EXAMPLE).unwrap();
         assert_eq!(res, 35);
     }
 
     #[test]
     fn day5_part1() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 5)?;
-        let res = solve_part1(&input);
-        println!("day5 Part1 Result: {res}");
+        let res = Day5::solve_part1(&input).unwrap();
+        assert_answer(2023, 5, Part::One, &res);
         Ok(())
     }
 
     #[test]
     fn day5_part2_test() {
-        let res = solve_part2(EXAMPLE);
+        let res = Day5::solve_part2(EXAMPLE).unwrap();
         assert_eq!(res, 46);
     }
 
     #[test]
     fn day5_part2() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 5)?;
-        let res = solve_part2(&input);
-        println!("day5 Part2 Result: {res}");
+        let res = Day5::solve_part2(&input).unwrap();
+        assert_answer(2023, 5, Part::Two, &res);
         Ok(())
     }
 }
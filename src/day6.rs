@@ -1,9 +1,12 @@
 use std::{collections::HashSet, error::Error, num, str::FromStr};
+
+use crate::error::{parse_in_line, AocError, AocResult};
+use crate::solution::{Answer, Params, Solution};
 const EXAMPLE: &str = r#"Time:      7  15   30
 Distance:  9  40  200"#;
 
 #[derive(Debug, Clone, Copy)]
-struct Race {
+pub struct Race {
     time: u64,
     distance: u64,
 }
@@ -26,28 +29,37 @@ fn distance(hold_time: u64, max_time: u64) -> u64 {
     (max_time - hold_time) * hold_time
 }
 
-fn parse(input: &str) -> Vec<Race> {
-    let mut lines = input.lines();
-    let times = lines
-        .next()
-        .unwrap()
-        .split(" ")
-        .filter_map(|v| v.parse::<u64>().ok())
-        .collect::<Vec<_>>();
-    let distances = lines
-        .next()
-        .unwrap()
-        .split(" ")
-        .filter_map(|v| v.parse::<u64>().ok())
-        .collect::<Vec<_>>();
+// Returns the values after `label` on the given (0-based) line
+fn parse_line<'a>(input: &'a str, row: usize, label: &str) -> AocResult<(&'a str, &'a str)> {
+    let line = input
+        .lines()
+        .nth(row)
+        .ok_or_else(|| AocError::new(format!("missing '{label}' line")))?;
+    let values = line
+        .strip_prefix(label)
+        .ok_or_else(|| AocError::at(row + 1, 1, format!("expected '{label}'")))?;
+    Ok((line, values))
+}
+
+fn parse_values(input: &str, row: usize, label: &str) -> AocResult<Vec<u64>> {
+    let (line, values) = parse_line(input, row, label)?;
+    values
+        .split_whitespace()
+        .map(|v| parse_in_line(line, v).map_err(|e| e.on_line(row + 1)))
+        .collect()
+}
+
+fn parse(input: &str) -> AocResult<Vec<Race>> {
+    let times = parse_values(input, 0, "Time:")?;
+    let distances = parse_values(input, 1, "Distance:")?;
 
     if times.len() != distances.len() {
-        println!(
-            "Bad parsing of input: {} != {}",
+        return Err(AocError::new(format!(
+            "{} times but {} distances",
             times.len(),
             distances.len()
-        );
-        return Vec::default();
+        ))
+        .on_line(2));
     }
 
     let mut races = Vec::default();
@@ -58,76 +70,77 @@ fn parse(input: &str) -> Vec<Race> {
         })
     }
 
-    races
+    Ok(races)
 }
 
-fn parse2(input: &str) -> Race {
-    let mut lines = input.lines();
-    let times = lines
-        .next()
-        .unwrap()
-        .split("Time:")
-        .nth(1)
-        .unwrap()
-        .split(" ")
-        .collect::<String>();
-    let distances = lines
-        .next()
-        .unwrap()
-        .split("Distance:")
-        .nth(1)
-        .unwrap()
-        .split(" ")
-        .collect::<String>();
-
-    Race {
-        time: times.parse().unwrap(),
-        distance: distances.parse().unwrap(),
-    }
+fn parse_joined(input: &str, row: usize, label: &str) -> AocResult<u64> {
+    let (line, values) = parse_line(input, row, label)?;
+    let digits = values.split_whitespace().collect::<String>();
+    digits
+        .parse()
+        .map_err(|e| AocError::in_line(line, values, format!("invalid number '{digits}': {e}")))
+        .map_err(|e| e.on_line(row + 1))
 }
 
-fn solve_part1(input: &str) -> u64 {
-    let races = parse(input);
-    races.iter().fold(1, |acc, r| acc * r.solve())
+fn parse2(input: &str) -> AocResult<Race> {
+    Ok(Race {
+        time: parse_joined(input, 0, "Time:")?,
+        distance: parse_joined(input, 1, "Distance:")?,
+    })
 }
 
-fn solve_part2(input: &str) -> u64 {
-    parse2(input).solve()
+pub struct Day6;
+
+impl Solution for Day6 {
+    // Part 1 reads the races column by column, part 2 reads each line as one number
+    type Input = (Vec<Race>, Race);
+
+    fn parse(input: &str) -> AocResult<Self::Input> {
+        Ok((parse(input)?, parse2(input)?))
+    }
+
+    fn part1((races, _): &Self::Input, _params: &Params) -> Answer {
+        races.iter().fold(1, |acc, r| acc * r.solve()).into()
+    }
+
+    fn part2((_, race): &Self::Input, _params: &Params) -> Answer {
+        race.solve().into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::error::Error;
 
-    use crate::get_input;
+    use crate::{answers::assert_answer, get_input, solution::Part};
 
     use super::*;
 
     #[test]
     fn day6_part1_test() {
-        let res = solve_part1(EXAMPLE);
+        let res = Day6::solve_part1(EXAMPLE).unwrap();
         assert_eq!(res, 288)
     }
 
     #[test]
     fn day6_part1() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 6)?;
-        let res = solve_part1(&input);
-        println!("day6 Part1 Result: {res}");
+        let res = Day6::solve_part1(&input).unwrap();
+        assert_answer(2023, 6, Part::One, &res);
         Ok(())
     }
 
     #[test]
     fn day6_part2_test() {
-        let res = solve_part2(EXAMPLE);
+        let res = Day6::solve_part2(EXAMPLE).unwrap();
         assert_eq!(res, 71503);
     }
 
     #[test]
     fn day6_part2() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 6)?;
-        let res = solve_part2(&input);
-        println!("day6 Part2 Result: {res}");
+        let res = Day6::solve_part2(&input).unwrap();
+        assert_answer(2023, 6, Part::Two, &res);
         Ok(())
     }
 }
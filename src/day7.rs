@@ -1,4 +1,7 @@
 use std::{cmp::Ordering, collections::HashSet, error::Error, num, str::FromStr};
+
+use crate::error::{parse_in_line, parse_lines, AocError, AocResult};
+use crate::solution::{Answer, Params, Solution};
 const EXAMPLE: &str = r#"32T3K 765
 T55J5 684
 KK677 28
@@ -53,7 +56,7 @@ enum HandType {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Hand {
+pub struct Hand {
     cards: [Card; 5],
     bid: u32,
     kind: HandType,
@@ -155,47 +158,48 @@ impl Hand {
     }
 }
 
-impl FromStr for Hand {
-    type Err = Box<dyn Error>;
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut hand = Hand {
-            cards: [Card::default(); 5],
-            bid: 0,
-            kind: HandType::HighCard,
-            old_kind: HandType::HighCard,
-        };
-        let mut parts = s.split(' ');
-        let cards = parts.next().ok_or("missing cards")?;
-        for (i, card) in cards.chars().enumerate() {
-            if i >= 5 {
-                break;
-            }
-
-            hand.cards[i] = Card(card);
+fn parse_hand(line: &str) -> AocResult<Hand> {
+    let mut hand = Hand {
+        cards: [Card::default(); 5],
+        bid: 0,
+        kind: HandType::HighCard,
+        old_kind: HandType::HighCard,
+    };
+    let (cards, bid) = line
+        .split_once(' ')
+        .ok_or_else(|| AocError::in_line(line, &line[line.len()..], "missing bid"))?;
+    if cards.len() != 5 {
+        return Err(AocError::in_line(
+            line,
+            cards,
+            format!("expected 5 cards, found {}", cards.len()),
+        ));
+    }
+    for (i, card) in cards.char_indices() {
+        hand.cards[i] = Card(card);
+        if hand.cards[i].strength() == 0 {
+            return Err(AocError::in_line(
+                line,
+                &cards[i..],
+                format!("unknown card '{card}'"),
+            ));
         }
+    }
 
-        hand.bid = parts.next().ok_or("missing bid")?.parse()?;
+    hand.bid = parse_in_line(line, bid)?;
 
-        Ok(hand)
-    }
+    Ok(hand)
 }
 
-fn parse(input: &str) -> Vec<Hand> {
-    input
-        .lines()
-        .filter_map(|l| match Hand::from_str(l) {
-            Ok(h) => Some(h),
-            Err(e) => {
-                println!("Bad Hand: {:?}", e);
-                None
-            }
-        })
-        .collect()
+fn parse(input: &str) -> AocResult<Vec<Hand>> {
+    parse_lines(input, parse_hand)
 }
 
-fn solve_part1(input: &str) -> u32 {
-    let mut hands = parse(input);
-    hands.iter_mut().for_each(|h| h.determine_hand_type(false));
+fn total_winnings(hands: &[Hand], include_jokers: bool) -> u32 {
+    let mut hands = hands.to_vec();
+    hands
+        .iter_mut()
+        .for_each(|h| h.determine_hand_type(include_jokers));
     hands.sort();
     hands
         .iter()
@@ -204,56 +208,57 @@ fn solve_part1(input: &str) -> u32 {
         .sum()
 }
 
-fn solve_part2(input: &str) -> u32 {
-    let mut hands = parse(input);
-    hands.iter_mut().for_each(|h| h.determine_hand_type(true));
-    hands.sort();
+pub struct Day7;
 
-    for (i, h) in hands.iter().enumerate() {
-        if h.kind != h.old_kind {
-            println!("[{i}] {:?}", h);
-        }
+impl Solution for Day7 {
+    type Input = Vec<Hand>;
+
+    fn parse(input: &str) -> AocResult<Self::Input> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Answer {
+        total_winnings(input, false).into()
+    }
+
+    fn part2(input: &Self::Input, _params: &Params) -> Answer {
+        total_winnings(input, true).into()
     }
-    hands
-        .iter()
-        .enumerate()
-        .map(|(i, h)| (i + 1) as u32 * h.bid)
-        .sum()
 }
 
 #[cfg(test)]
 mod tests {
     use std::error::Error;
 
-    use crate::get_input;
+    use crate::{answers::assert_answer, get_input, solution::Part};
 
     use super::*;
 
     #[test]
     fn day7_part1_test() {
-        let res = solve_part1(EXAMPLE);
+        let res = Day7::solve_part1(EXAMPLE).unwrap();
         assert_eq!(res, 6440)
     }
 
     #[test]
     fn day7_part1() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 7)?;
-        let res = solve_part1(&input);
-        println!("day7 Part1 Result: {res}");
+        let res = Day7::solve_part1(&input).unwrap();
+        assert_answer(2023, 7, Part::One, &res);
         Ok(())
     }
 
     #[test]
     fn day7_part2_test() {
-        let res = solve_part2(EXAMPLE_2);
+        let res = Day7::solve_part2(EXAMPLE_2).unwrap();
         assert_eq!(res, 5905);
     }
 
     #[test]
     fn day7_part2() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 7)?;
-        let res = solve_part2(&input);
-        println!("day7 Part2 Result: {res}");
+        let res = Day7::solve_part2(&input).unwrap();
+        assert_answer(2023, 7, Part::Two, &res);
         Ok(())
     }
 }
@@ -1,4 +1,13 @@
-use std::{cmp::Ordering, collections::HashMap, error::Error, num, str::FromStr};
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    error::Error,
+    num,
+    str::FromStr,
+};
+
+use crate::error::{AocError, AocResult};
+use crate::solution::{Answer, Params, Solution};
 const EXAMPLE: &str = r#"RL
 
 AAA = (BBB, CCC)
@@ -27,30 +36,94 @@ const EXAMPLE_PART2: &str = r#"LR
 XXX = (XXX, XXX)
 "#;
 
-fn parse(input: &str) -> (String, HashMap<String, (String, String)>) {
-    let mut lines = input.lines();
-    let instructions = lines.next().unwrap().to_owned();
+// `AAA = (BBB, CCC)`
+fn parse_node(line: &str) -> AocResult<(&str, (&str, &str))> {
+    let (key, lr) = line
+        .split_once(" = ")
+        .ok_or_else(|| AocError::in_line(line, line, "expected '<node> = (<left>, <right>)'"))?;
+    let (left, right) = lr
+        .strip_prefix('(')
+        .and_then(|lr| lr.strip_suffix(')'))
+        .and_then(|lr| lr.split_once(", "))
+        .ok_or_else(|| AocError::in_line(line, lr, "expected '(<left>, <right>)'"))?;
+    Ok((key, (left, right)))
+}
+
+fn parse(input: &str) -> AocResult<Network> {
+    let mut lines = input.lines().enumerate();
+    let (_, instructions) = lines.next().ok_or("missing instructions")?;
+    if instructions.is_empty() {
+        return Err(AocError::at(1, 1, "missing instructions"));
+    }
+    if let Some(col) = instructions.find(|c| c != 'L' && c != 'R') {
+        return Err(AocError::at(1, col + 1, "expected only 'L' and 'R'"));
+    }
 
     lines.next();
 
     let nodes = lines
-        .filter_map(|l| {
-            let mut parts = l.split(" = ");
-            let key = parts.next().unwrap().to_owned();
-
-            let mut lr = parts.next().unwrap().split(", ");
-            let left = lr.next().unwrap().strip_prefix('(').unwrap().to_owned();
-            let right = lr.next().unwrap().strip_suffix(')').unwrap().to_owned();
-            Some((key, (left, right)))
+        .map(|(i, l)| {
+            parse_node(l)
+                .map(|node| (i + 1, l, node))
+                .map_err(|e| e.on_line(i + 1))
         })
-        .collect();
+        .collect::<AocResult<Vec<_>>>()?;
+    let network: Network = (
+        instructions.to_owned(),
+        nodes
+            .iter()
+            .map(|(_, _, (key, (left, right)))| {
+                (key.to_string(), (left.to_string(), right.to_string()))
+            })
+            .collect(),
+    );
+
+    for (line_no, line, (_, (left, right))) in &nodes {
+        if let Some(next) = [left, right]
+            .into_iter()
+            .find(|next| !network.1.contains_key(**next))
+        {
+            return Err(
+                AocError::in_line(line, next, format!("undefined node '{next}'")).on_line(*line_no),
+            );
+        }
+    }
 
-    (instructions, nodes)
+    Ok(network)
 }
 
-fn solve_part1(input: &str) -> u32 {
-    let (instructions, nodes) = parse(input);
+// Why the walk from AAA to ZZZ of part 1 would never end, the part 2 example
+// has neither node
+fn endless_walk(network: &Network) -> Option<&'static str> {
+    if !network.1.contains_key("AAA") {
+        Some("missing the start node AAA")
+    } else if !reaches(network, "AAA", |node| node == "ZZZ") {
+        Some("ZZZ can not be reached from AAA")
+    } else {
+        None
+    }
+}
 
+// Whether following the instructions from `start` ever gets to an end node
+fn reaches((instructions, nodes): &Network, start: &str, is_end: impl Fn(&str) -> bool) -> bool {
+    let mut seen = HashSet::new();
+    let mut node = start;
+    for (i, inst) in instructions.chars().enumerate().cycle() {
+        if is_end(node) {
+            return true;
+        }
+        if !seen.insert((node, i)) {
+            return false;
+        }
+        let (left, right) = &nodes[node];
+        node = if inst == 'L' { left } else { right };
+    }
+    unreachable!("the instructions are not empty")
+}
+
+type Network = (String, HashMap<String, (String, String)>);
+
+fn steps_to_end((instructions, nodes): &Network) -> u32 {
     let mut next_node = "AAA";
     let mut steps = 0;
     while next_node != "ZZZ" {
@@ -71,9 +144,7 @@ fn solve_part1(input: &str) -> u32 {
     steps
 }
 
-fn solve_part2(input: &str) -> u64 {
-    let (instructions, nodes) = parse(input);
-
+fn ghost_steps((instructions, nodes): &Network) -> u64 {
     let mut start_nodes = nodes
         .keys()
         .filter(|k| k.ends_with('A'))
@@ -93,7 +164,6 @@ fn solve_part2(input: &str) -> u64 {
             for inst in instructions.chars() {
                 if next_node.contains('Z') {
                     z_has_been_seen = true;
-                    println!("{} : {}", next_node, steps);
                 }
 
                 let node = &nodes[next_node];
@@ -117,52 +187,104 @@ fn solve_part2(input: &str) -> u64 {
         }
 
         cycle_steps.push((next_node.to_owned(), last_seen_steps, steps));
-        println!("{:?}", cycle_steps.last());
     }
 
-    for (_, ls, s) in cycle_steps {
-        print!(" {},", s - ls);
+    0
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = Network;
+
+    fn parse(input: &str) -> AocResult<Self::Input> {
+        parse(input)
     }
 
-    0
+    fn warnings(input: &Self::Input) -> Vec<String> {
+        endless_walk(input)
+            .map(|issue| format!("part 1 has no answer: {issue}"))
+            .into_iter()
+            .collect()
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Answer {
+        if endless_walk(input).is_some() {
+            return Answer::Unsolved;
+        }
+        steps_to_end(input).into()
+    }
+
+    // `ghost_steps` finds the cycle of every ghost but does not combine them
+    // into an answer yet
+    fn part2(_input: &Self::Input, _params: &Params) -> Answer {
+        Answer::Unsolved
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::error::Error;
 
-    use crate::get_input;
+    use crate::{answers::assert_answer, get_input, solution::Part};
 
     use super::*;
 
     #[test]
     fn day8_part1_test() {
-        let res = solve_part1(EXAMPLE);
+        let res = Day8::solve_part1(EXAMPLE).unwrap();
         assert_eq!(res, 2);
 
-        let res = solve_part1(EXAMPLE_2);
+        let res = Day8::solve_part1(EXAMPLE_2).unwrap();
         assert_eq!(res, 6);
     }
 
     #[test]
     fn day8_part1() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 8)?;
-        let res = solve_part1(&input);
-        println!("day8 Part1 Result: {res}");
+        let res = Day8::solve_part1(&input).unwrap();
+        assert_answer(2023, 8, Part::One, &res);
         Ok(())
     }
 
+    #[test]
+    fn day8_parse_errors() {
+        let err = Day8::parse("RL\n\nAAA = (BBB, CCC)").unwrap_err();
+        assert_eq!(err.to_string(), "line 3, column 8: undefined node 'BBB'");
+        let err = Day8::parse("\n\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(1), Some(1)));
+    }
+
+    #[test]
+    fn day8_endless_walk() {
+        let network = Day8::parse("L\n\nAAA = (AAA, ZZZ)\nZZZ = (ZZZ, ZZZ)").unwrap();
+        assert_eq!(
+            Day8::warnings(&network),
+            ["part 1 has no answer: ZZZ can not be reached from AAA"]
+        );
+        assert_eq!(Day8::part1(&network, &Day8::params()), Answer::Unsolved);
+
+        let network = Day8::parse(EXAMPLE_PART2).unwrap();
+        assert_eq!(
+            Day8::warnings(&network),
+            ["part 1 has no answer: missing the start node AAA"]
+        );
+        assert_eq!(Day8::part1(&network, &Day8::params()), Answer::Unsolved);
+        assert!(Day8::warnings(&Day8::parse(EXAMPLE).unwrap()).is_empty());
+    }
+
     // #[test]
     // fn day8_part2_test() {
-    //     let res = solve_part2(EXAMPLE_PART2);
+    //     let res = Day8::solve_part2(EXAMPLE_PART2).unwrap();
     //     assert_eq!(res, 6);
     // }
 
     #[test]
+    #[ignore = "part 2 never combines the ghost cycles into an answer"]
     fn day8_part2() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 8)?;
-        let res = solve_part2(&input);
-        println!("day8 Part2 Result: {res}");
+        let res = Day8::solve_part2(&input).unwrap();
+        assert_answer(2023, 8, Part::Two, &res);
 
         // let factors = vec![2 , 2 , 2 , 2 , 2 , 2 , 2 , 2 , 2 , 2 , 2 , 2 , 2 , 2 , 2 , 2 , 2 , 2 , 2 , 2 , 2 , 2 , 2 , 2 , 2 , 3 , 19 , 1879049 , 69751037u64];
         // let res = factors.iter().fold(1, |acc, v| acc * *v);
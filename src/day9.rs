@@ -1,21 +1,20 @@
 use std::{cmp::Ordering, collections::HashMap, error::Error, num, str::FromStr};
+
+use crate::error::{parse_in_line, parse_lines, AocError, AocResult};
+use crate::solution::{Answer, Params, Solution};
 const EXAMPLE: &str = r#"0 3 6 9 12 15
 1 3 6 10 15 21
 10 13 16 21 30 45"#;
 
-fn solve(input: &str) -> (i32, i32) {
+fn extrapolate(sequences: &[Vec<i32>]) -> (i32, i32) {
     let mut last_vals = Vec::new();
     let mut first_vals = Vec::new();
     let mut current_sequence = Vec::new();
     let mut next_sequence = Vec::new();
     let mut sum = (0, 0);
-    input.lines().for_each(|line| {
+    sequences.iter().for_each(|sequence| {
         current_sequence.clear();
-        current_sequence.extend(
-            line.split(' ')
-                .map(str::parse::<i32>)
-                .filter_map(|res| res.ok()),
-        );
+        current_sequence.extend(sequence.iter().copied());
 
         last_vals.push(current_sequence.last().copied().unwrap());
         first_vals.push(current_sequence.first().copied().unwrap());
@@ -37,7 +36,7 @@ fn solve(input: &str) -> (i32, i32) {
 
         let mut last_val = 0;
         while let Some(val) = last_vals.pop() {
-            last_val = val + last_val;
+            last_val += val;
         }
         let mut first_val = 0;
         while let Some(val) = first_vals.pop() {
@@ -48,21 +47,42 @@ fn solve(input: &str) -> (i32, i32) {
     sum
 }
 
-fn solve_part2(_input: &str) -> u32 {
-    0
+pub struct Day9;
+
+impl Solution for Day9 {
+    type Input = Vec<Vec<i32>>;
+
+    fn parse(input: &str) -> AocResult<Self::Input> {
+        parse_lines(input, |line| {
+            if line.trim().is_empty() {
+                return Err(AocError::new("empty sequence"));
+            }
+            line.split_whitespace()
+                .map(|n| parse_in_line(line, n))
+                .collect()
+        })
+    }
+
+    fn part1(input: &Self::Input, _params: &Params) -> Answer {
+        extrapolate(input).1.into()
+    }
+
+    fn part2(input: &Self::Input, _params: &Params) -> Answer {
+        extrapolate(input).0.into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use std::error::Error;
 
-    use crate::get_input;
+    use crate::{answers::assert_answer, get_input, solution::Part};
 
     use super::*;
 
     #[test]
     fn day9_test() {
-        let res = solve(EXAMPLE);
+        let res = extrapolate(&Day9::parse(EXAMPLE).unwrap());
         println!("day9 example {res:?}");
         assert_eq!(res, (2, 114));
     }
@@ -70,21 +90,23 @@ mod tests {
     #[test]
     fn day9() -> Result<(), Box<dyn Error>> {
         let input = get_input(2023, 9)?;
-        let res = solve(&input);
-        println!("day9 result: {res:?}");
+        let res = Day9::solve_part1(&input).unwrap();
+        assert_answer(2023, 9, Part::One, &res);
+        let res = Day9::solve_part2(&input).unwrap();
+        assert_answer(2023, 9, Part::Two, &res);
         Ok(())
     }
 
     // #[test]
     // fn day9_part2_test() {
-    //     let res = solve_part2(EXAMPLE_2);
+    //     let res = Day9::solve_part2(EXAMPLE_2).unwrap();
     //     assert_eq!(res, 5905);
     // }
 
     // #[test]
     // fn day9_part2() -> Result<(), Box<dyn Error>> {
     //     let input = get_input(2023, 9)?;
-    //     let res = solve_part2(&input);
+    //     let res = Day9::solve_part2(&input).unwrap();
     //     println!("day9 Part2 Result: {res}");
     //     Ok(())
     // }
@@ -0,0 +1,119 @@
+use std::{error::Error, fmt::Display, str::FromStr};
+
+// A parse failure with the position in the puzzle input it was found at.
+// Lines and columns are 1-based, like in an editor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AocError {
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+pub type AocResult<T> = Result<T, AocError>;
+
+impl AocError {
+    pub fn new(message: impl Into<String>) -> Self {
+        AocError {
+            line: None,
+            column: None,
+            message: message.into(),
+        }
+    }
+
+    pub fn at(line: usize, column: usize, message: impl Into<String>) -> Self {
+        AocError {
+            line: Some(line),
+            column: Some(column),
+            message: message.into(),
+        }
+    }
+
+    // Points at `token`, which has to be a slice of `line`
+    pub fn in_line(line: &str, token: &str, message: impl Into<String>) -> Self {
+        let offset = (token.as_ptr() as usize).wrapping_sub(line.as_ptr() as usize);
+        let column = (offset.saturating_add(token.len()) <= line.len()).then_some(offset + 1);
+        AocError {
+            line: None,
+            column,
+            message: message.into(),
+        }
+    }
+
+    // Per line parsers don't know which line they are on, the caller fills it in
+    pub fn on_line(mut self, line: usize) -> Self {
+        self.line.get_or_insert(line);
+        self
+    }
+}
+
+impl Display for AocError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.line, self.column) {
+            (Some(line), Some(column)) => write!(f, "line {line}, column {column}: ")?,
+            (Some(line), None) => write!(f, "line {line}: ")?,
+            _ => {}
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+impl Error for AocError {}
+
+impl From<String> for AocError {
+    fn from(value: String) -> Self {
+        AocError::new(value)
+    }
+}
+
+impl From<&str> for AocError {
+    fn from(value: &str) -> Self {
+        AocError::new(value)
+    }
+}
+
+// Runs `parse` on every line and adds the line number to its errors
+pub fn parse_lines<T>(
+    input: &str,
+    mut parse: impl FnMut(&str) -> AocResult<T>,
+) -> AocResult<Vec<T>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| parse(line).map_err(|e| e.on_line(i + 1)))
+        .collect()
+}
+
+// Parses `token`, a slice of `line`, pointing at it on failure
+pub fn parse_in_line<T: FromStr>(line: &str, token: &str) -> AocResult<T>
+where
+    T::Err: Display,
+{
+    token
+        .parse()
+        .map_err(|e| AocError::in_line(line, token, format!("invalid number '{token}': {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn errors_point_into_the_input() {
+        let input = "1 2\n3 x 5\n";
+        let err = parse_lines(input, |line| {
+            line.split(' ')
+                .map(|n| parse_in_line::<u32>(line, n))
+                .collect::<AocResult<Vec<_>>>()
+        })
+        .unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), Some(3)));
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 3: invalid number 'x': invalid digit found in string"
+        );
+
+        let err = AocError::in_line("abc", "other", "not a slice");
+        assert_eq!(err.column, None);
+        assert_eq!(AocError::new("bad").to_string(), "bad");
+    }
+}
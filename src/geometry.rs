@@ -0,0 +1,212 @@
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    // Clockwise, starting at the top
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn turn_left(self) -> Self {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Right => Direction::Up,
+            Direction::Down => Direction::Right,
+            Direction::Left => Direction::Down,
+        }
+    }
+
+    pub fn turn_right(self) -> Self {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn reverse(self) -> Self {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Right => Direction::Left,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+        }
+    }
+
+    // `(dx, dy)` with `y` growing downwards, like the rows of a grid
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Direction::Up => (0, -1),
+            Direction::Right => (1, 0),
+            Direction::Down => (0, 1),
+            Direction::Left => (-1, 0),
+        }
+    }
+
+    pub fn is_vertical(self) -> bool {
+        matches!(self, Direction::Up | Direction::Down)
+    }
+
+    // `^`, `>`, `v` and `<`
+    pub fn from_arrow(c: char) -> Option<Self> {
+        match c {
+            '^' => Some(Direction::Up),
+            '>' => Some(Direction::Right),
+            'v' => Some(Direction::Down),
+            '<' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    pub fn arrow(self) -> char {
+        match self {
+            Direction::Up => '^',
+            Direction::Right => '>',
+            Direction::Down => 'v',
+            Direction::Left => '<',
+        }
+    }
+
+    // `U`, `R`, `D` and `L`
+    pub fn from_letter(c: char) -> Option<Self> {
+        match c {
+            'U' => Some(Direction::Up),
+            'R' => Some(Direction::Right),
+            'D' => Some(Direction::Down),
+            'L' => Some(Direction::Left),
+            _ => None,
+        }
+    }
+
+    pub fn letter(self) -> char {
+        match self {
+            Direction::Up => 'U',
+            Direction::Right => 'R',
+            Direction::Down => 'D',
+            Direction::Left => 'L',
+        }
+    }
+}
+
+impl Display for Direction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.arrow())
+    }
+}
+
+// Integer types a `Point` can be made of. Adding an offset is checked, so
+// stepping off the top or left of an unsigned grid gives `None` instead of
+// wrapping around.
+pub trait Coord: Copy {
+    fn add_offset(self, delta: isize) -> Option<Self>;
+    fn distance(self, other: Self) -> usize;
+}
+
+impl Coord for usize {
+    fn add_offset(self, delta: isize) -> Option<Self> {
+        self.checked_add_signed(delta)
+    }
+
+    fn distance(self, other: Self) -> usize {
+        self.abs_diff(other)
+    }
+}
+
+macro_rules! signed_coord {
+    ($($t:ty),*) => {$(
+        impl Coord for $t {
+            fn add_offset(self, delta: isize) -> Option<Self> {
+                self.checked_add(delta.try_into().ok()?)
+            }
+
+            fn distance(self, other: Self) -> usize {
+                self.abs_diff(other) as usize
+            }
+        }
+    )*};
+}
+
+signed_coord!(i32, i64, isize);
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point { x, y }
+    }
+}
+
+impl<T: Coord> Point<T> {
+    pub fn offset(self, dx: isize, dy: isize) -> Option<Self> {
+        Some(Point::new(self.x.add_offset(dx)?, self.y.add_offset(dy)?))
+    }
+
+    pub fn step(self, dir: Direction) -> Option<Self> {
+        self.step_n(dir, 1)
+    }
+
+    pub fn step_n(self, dir: Direction, n: usize) -> Option<Self> {
+        let n = isize::try_from(n).ok()?;
+        let (dx, dy) = dir.offset();
+        self.offset(dx.checked_mul(n)?, dy.checked_mul(n)?)
+    }
+
+    pub fn manhattan(self, other: Self) -> usize {
+        self.x.distance(other.x) + self.y.distance(other.y)
+    }
+}
+
+impl<T: Display> Display for Point<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turns() {
+        for dir in Direction::ALL {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.turn_right().turn_right(), dir.reverse());
+            assert_eq!(Direction::from_arrow(dir.arrow()), Some(dir));
+            assert_eq!(Direction::from_letter(dir.letter()), Some(dir));
+        }
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::from_arrow('x'), None);
+        assert!(Direction::Down.is_vertical());
+    }
+
+    #[test]
+    fn checked_steps() {
+        let origin = Point::new(0usize, 0);
+        assert_eq!(origin.step(Direction::Up), None);
+        assert_eq!(origin.step(Direction::Left), None);
+        assert_eq!(origin.step(Direction::Down), Some(Point::new(0, 1)));
+        assert_eq!(origin.step_n(Direction::Right, 3), Some(Point::new(3, 0)));
+        assert_eq!(
+            Point::new(2i32, 1).step_n(Direction::Left, 5),
+            Some(Point::new(-3, 1))
+        );
+        assert_eq!(Point::new(i32::MAX, 0).step(Direction::Right), None);
+        assert_eq!(Point::new(1usize, 5).manhattan(Point::new(4, 2)), 6);
+        assert_eq!(Point::new(-1i64, 5).manhattan(Point::new(4, -2)), 12);
+    }
+}
@@ -0,0 +1,320 @@
+use std::{
+    fmt::{Display, Write},
+    ops::{Index, IndexMut},
+};
+
+use crate::{
+    error::{AocError, AocResult},
+    geometry::{Direction, Point},
+};
+
+// `y` counts rows from the top
+pub type Pos = Point<usize>;
+
+const OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+];
+
+// A rectangular grid stored row by row
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    data: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn from_vec(width: usize, height: usize, data: Vec<T>) -> Self {
+        assert_eq!(
+            width * height,
+            data.len(),
+            "grid data does not match its size"
+        );
+        Grid {
+            width,
+            height,
+            data,
+        }
+    }
+
+    pub fn filled(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid::from_vec(width, height, vec![value; width * height])
+    }
+
+    // Every character goes through `cell`, a `None` is reported as an
+    // unexpected character with its line and column
+    pub fn parse_with(input: &str, mut cell: impl FnMut(u8) -> Option<T>) -> AocResult<Self> {
+        let width = input.lines().next().map_or(0, str::len);
+        let mut data = Vec::new();
+        let mut height = 0;
+        for (row, line) in input.lines().enumerate() {
+            for (col, b) in line.bytes().enumerate() {
+                let value = cell(b).ok_or_else(|| {
+                    let c = line[col..].chars().next().unwrap();
+                    AocError::at(row + 1, col + 1, format!("unexpected '{c}'"))
+                })?;
+                data.push(value);
+            }
+            if line.len() != width {
+                return Err(AocError::at(
+                    row + 1,
+                    line.len().min(width) + 1,
+                    format!("expected {width} columns, found {}", line.len()),
+                ));
+            }
+            height += 1;
+        }
+        Ok(Grid::from_vec(width, height, data))
+    }
+
+    // For puzzles that start on a tile, an input without any is an error
+    pub fn non_empty(self) -> AocResult<Self> {
+        if self.data.is_empty() {
+            return Err(AocError::at(1, 1, "expected a grid, found no tiles"));
+        }
+        Ok(self)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, pos: Pos) -> bool {
+        pos.x < self.width && pos.y < self.height
+    }
+
+    pub fn get(&self, pos: Pos) -> Option<&T> {
+        self.contains(pos)
+            .then(|| &self.data[pos.y * self.width + pos.x])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos) -> Option<&mut T> {
+        self.contains(pos)
+            .then(|| &mut self.data[pos.y * self.width + pos.x])
+    }
+
+    // Returns false when `pos` lies outside of the grid
+    pub fn set(&mut self, pos: Pos, value: T) -> bool {
+        match self.get_mut(pos) {
+            Some(cell) => {
+                *cell = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.data[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` refuses a size of 0, an empty grid has no rows anyway
+        self.data.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} is outside of the grid");
+        self.data.iter().skip(x).step_by(self.width)
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point::new(x, y)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos, &T)> {
+        self.positions().zip(self.data.iter())
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.data.iter_mut()
+    }
+
+    pub fn find(&self, mut pred: impl FnMut(&T) -> bool) -> Option<Pos> {
+        self.iter()
+            .find_map(|(pos, value)| pred(value).then_some(pos))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid::from_vec(self.width, self.height, self.data.iter().map(f).collect())
+    }
+
+    // The next position in `dir`, if it is still inside of the grid
+    pub fn step(&self, pos: Pos, dir: Direction) -> Option<Pos> {
+        pos.step(dir).filter(|&next| self.contains(next))
+    }
+
+    // Up, right, down and left of `pos`, skipping the ones outside
+    pub fn neighbours4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |dir| self.step(pos, dir))
+    }
+
+    // Includes the diagonals, clockwise from the top left
+    pub fn neighbours8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        OFFSETS_8
+            .iter()
+            .filter_map(move |&(dx, dy)| pos.offset(dx, dy))
+            .filter(|&next| self.contains(next))
+    }
+
+    pub fn render(&self, mut cell: impl FnMut(&T) -> char) -> String {
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            out.extend(row.iter().map(&mut cell));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl<T: Clone> Grid<T> {
+    // `source` maps every `(x, y)` of the new grid to the one it is copied from
+    fn rearranged(
+        &self,
+        width: usize,
+        height: usize,
+        source: impl Fn(usize, usize) -> (usize, usize),
+    ) -> Self {
+        let data = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let (x, y) = source(x, y);
+                self[Point::new(x, y)].clone()
+            })
+            .collect();
+        Grid::from_vec(width, height, data)
+    }
+
+    // Rows become columns
+    pub fn transpose(&self) -> Self {
+        self.rearranged(self.height, self.width, |x, y| (y, x))
+    }
+
+    pub fn rotate_clockwise(&self) -> Self {
+        let height = self.height;
+        self.rearranged(self.height, self.width, |x, y| (y, height - 1 - x))
+    }
+
+    pub fn rotate_counter_clockwise(&self) -> Self {
+        let width = self.width;
+        self.rearranged(self.height, self.width, |x, y| (width - 1 - y, x))
+    }
+
+    // Mirrors left to right
+    pub fn flip_horizontal(&self) -> Self {
+        let width = self.width;
+        self.rearranged(self.width, self.height, |x, y| (width - 1 - x, y))
+    }
+
+    // Mirrors top to bottom
+    pub fn flip_vertical(&self) -> Self {
+        let height = self.height;
+        self.rearranged(self.width, self.height, |x, y| (x, height - 1 - y))
+    }
+}
+
+impl Grid<u8> {
+    // Parses a grid of raw bytes, only the `allowed` ones are accepted
+    pub fn parse(input: &str, allowed: &[u8]) -> AocResult<Self> {
+        Grid::parse_with(input, |b| allowed.contains(&b).then_some(b))
+    }
+}
+
+// Unchecked indexing, panics outside of the grid
+impl<T> Index<Pos> for Grid<T> {
+    type Output = T;
+    fn index(&self, pos: Pos) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside of the grid"))
+    }
+}
+
+impl<T> IndexMut<Pos> for Grid<T> {
+    fn index_mut(&mut self, pos: Pos) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos:?} is outside of the grid"))
+    }
+}
+
+impl Display for Grid<u8> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render(|b| *b as char))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "#..\n.#.\n..#\n##.";
+
+    #[test]
+    fn parse_and_render() {
+        let grid = Grid::parse(EXAMPLE, b".#").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 4));
+        assert_eq!(grid[Point::new(1, 1)], b'#');
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.row(3), b"##.");
+        assert_eq!(grid.column(0).copied().collect::<Vec<_>>(), b"#..#");
+        assert_eq!(grid.find(|b| *b == b'#'), Some(Point::new(0, 0)));
+        assert_eq!(grid.to_string(), format!("{EXAMPLE}\n"));
+
+        let err = Grid::parse("..\n.x", b".").unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 2: unexpected 'x'");
+        let err = Grid::parse("..\n.", b".").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 2: expected 2 columns, found 1"
+        );
+
+        assert!(Grid::parse("", b".").unwrap().non_empty().is_err());
+        assert!(Grid::parse("\n", b".").unwrap().non_empty().is_err());
+        assert!(Grid::parse(".", b".").unwrap().non_empty().is_ok());
+
+        let digits = Grid::parse_with("12\n34", |b| b.is_ascii_digit().then(|| b - b'0')).unwrap();
+        assert_eq!(digits.render(|d| (b'a' + d) as char), "bc\nde\n");
+    }
+
+    #[test]
+    fn neighbours() {
+        let grid = Grid::filled(3, 2, 0);
+        let neighbours4 = |x, y| {
+            grid.neighbours4(Point::new(x, y))
+                .map(|p| (p.x, p.y))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(neighbours4(0, 0), [(1, 0), (0, 1)]);
+        assert_eq!(neighbours4(1, 1), [(1, 0), (2, 1), (0, 1)]);
+        assert_eq!(grid.neighbours8(Point::new(1, 0)).count(), 5);
+        assert_eq!(grid.neighbours8(Point::new(2, 1)).count(), 3);
+        assert_eq!(grid.step(Point::new(2, 0), Direction::Right), None);
+    }
+
+    #[test]
+    fn transformations() {
+        let grid = Grid::parse("ab\ncd\nef", b"abcdef").unwrap();
+        assert_eq!(grid.transpose().to_string(), "ace\nbdf\n");
+        assert_eq!(grid.rotate_clockwise().to_string(), "eca\nfdb\n");
+        assert_eq!(grid.rotate_counter_clockwise().to_string(), "bdf\nace\n");
+        assert_eq!(grid.flip_horizontal().to_string(), "ba\ndc\nfe\n");
+        assert_eq!(grid.flip_vertical().to_string(), "ef\ncd\nab\n");
+        assert_eq!(grid.rotate_clockwise().rotate_counter_clockwise(), grid);
+    }
+}
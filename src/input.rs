@@ -0,0 +1,491 @@
+use std::{
+    collections::HashMap,
+    env,
+    error::Error,
+    fmt::Display,
+    fs::{self, read_to_string},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+use reqwest::blocking::{Client, RequestBuilder};
+
+use crate::solution::Part;
+
+pub const AOC_URL: &str = "https://adventofcode.com";
+
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+const MIN_REQUEST_INTERVAL: Duration = Duration::from_secs(3);
+
+// Shared by every provider so separate instances can't hammer the server
+static LAST_REQUEST: Mutex<Option<Instant>> = Mutex::new(None);
+
+// Year of the inputs stored in the old flat `inputs/day_<n>_input.txt` layout
+const FLAT_LAYOUT_YEAR: u32 = 2023;
+
+pub trait InputProvider {
+    fn get_input(&self, year: u32, day: u32) -> Result<String, Box<dyn Error>>;
+}
+
+// Inputs of one account stored as `<dir>/<year>/day_<n>.txt`
+pub struct FsProvider {
+    dir: PathBuf,
+}
+
+impl FsProvider {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FsProvider { dir: dir.into() }
+    }
+
+    pub fn path(&self, year: u32, day: u32) -> PathBuf {
+        self.dir
+            .join(year.to_string())
+            .join(format!("day_{day}.txt"))
+    }
+
+    pub fn store(&self, year: u32, day: u32, input: &str) -> Result<(), Box<dyn Error>> {
+        let path = self.path(year, day);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, input)?;
+        Ok(())
+    }
+}
+
+impl InputProvider for FsProvider {
+    fn get_input(&self, year: u32, day: u32) -> Result<String, Box<dyn Error>> {
+        let path = self.path(year, day);
+        read_to_string(&path).map_err(|e| format!("could not read {}: {e}", path.display()).into())
+    }
+}
+
+#[derive(Default)]
+pub struct MemoryProvider {
+    inputs: HashMap<(u32, u32), String>,
+}
+
+impl MemoryProvider {
+    pub fn with(mut self, year: u32, day: u32, input: &str) -> Self {
+        self.inputs.insert((year, day), input.to_owned());
+        self
+    }
+}
+
+impl InputProvider for MemoryProvider {
+    fn get_input(&self, year: u32, day: u32) -> Result<String, Box<dyn Error>> {
+        self.inputs
+            .get(&(year, day))
+            .cloned()
+            .ok_or(format!("no input for {year} day {day}").into())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FetchError {
+    MissingSession,
+    NotLoggedIn,
+    TooEarly,
+    NotFound,
+    Server(u16),
+    Status(u16),
+    Http(String),
+}
+
+impl Display for FetchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FetchError::MissingSession => write!(f, "Missing AOC Session token"),
+            FetchError::NotLoggedIn => write!(f, "session token was rejected, log in again"),
+            FetchError::TooEarly => write!(f, "puzzle is not unlocked yet"),
+            FetchError::NotFound => write!(f, "puzzle not found"),
+            FetchError::Server(status) => write!(f, "server error {status}"),
+            FetchError::Status(status) => write!(f, "unexpected status {status}"),
+            FetchError::Http(e) => write!(f, "request failed: {e}"),
+        }
+    }
+}
+
+impl Error for FetchError {}
+
+impl From<reqwest::Error> for FetchError {
+    fn from(value: reqwest::Error) -> Self {
+        FetchError::Http(value.to_string())
+    }
+}
+
+// The server answers some failures with a normal looking page, so the body is
+// checked before the status
+fn classify(status: u16, body: &str) -> Result<(), FetchError> {
+    if body.contains("Please don't repeatedly request") {
+        return Err(FetchError::TooEarly);
+    }
+    if body.contains("Puzzle inputs differ by user") || body.contains("Please log in") {
+        return Err(FetchError::NotLoggedIn);
+    }
+    match status {
+        200..=299 => Ok(()),
+        404 => Err(FetchError::NotFound),
+        500..=599 => Err(FetchError::Server(status)),
+        _ => Err(FetchError::Status(status)),
+    }
+}
+
+pub struct HttpProvider {
+    base_url: String,
+    session: Option<String>,
+    min_interval: Duration,
+}
+
+impl HttpProvider {
+    pub fn new(base_url: &str, session: Option<String>) -> Self {
+        HttpProvider {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            session,
+            min_interval: MIN_REQUEST_INTERVAL,
+        }
+    }
+
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    // AOC_BASE_URL lets the fetch path be pointed at a mock server
+    pub fn from_env() -> Self {
+        let base_url = env::var("AOC_BASE_URL").unwrap_or(AOC_URL.to_owned());
+        HttpProvider::new(&base_url, env::var("AOC_SESSION").ok())
+    }
+}
+
+impl HttpProvider {
+    pub fn fetch(&self, year: u32, day: u32) -> Result<String, FetchError> {
+        let url = format!("{}/{year}/day/{day}/input", self.base_url);
+        self.send(|client| client.get(url))
+    }
+
+    pub fn post_answer(
+        &self,
+        year: u32,
+        day: u32,
+        part: Part,
+        answer: &str,
+    ) -> Result<String, FetchError> {
+        let url = format!("{}/{year}/day/{day}/answer", self.base_url);
+        let level = part.to_string();
+        self.send(|client| {
+            client
+                .post(url)
+                .form(&[("level", level.as_str()), ("answer", answer)])
+        })
+    }
+
+    fn send(&self, request: impl FnOnce(&Client) -> RequestBuilder) -> Result<String, FetchError> {
+        let session = self.session.as_ref().ok_or(FetchError::MissingSession)?;
+        let client = Client::builder().user_agent(USER_AGENT).build()?;
+        let request =
+            request(&client).header(reqwest::header::COOKIE, format!("session={session}"));
+
+        // Keep the lock while waiting so concurrent requests queue up
+        let mut last_request = LAST_REQUEST.lock().unwrap();
+        if let Some(elapsed) = last_request.map(|last| last.elapsed()) {
+            if elapsed < self.min_interval {
+                thread::sleep(self.min_interval - elapsed);
+            }
+        }
+        let response = request.send();
+        *last_request = Some(Instant::now());
+        drop(last_request);
+
+        let response = response?;
+        let status = response.status().as_u16();
+        let body = response.text()?;
+        classify(status, &body)?;
+        Ok(body)
+    }
+}
+
+impl InputProvider for HttpProvider {
+    fn get_input(&self, year: u32, day: u32) -> Result<String, Box<dyn Error>> {
+        Ok(self.fetch(year, day)?)
+    }
+}
+
+// Serves inputs from `cache` and stores anything fetched from `remote` in it.
+// Failed fetches are never stored.
+pub struct CachingProvider<R> {
+    cache: FsProvider,
+    remote: R,
+}
+
+impl<R: InputProvider> CachingProvider<R> {
+    pub fn new(cache: FsProvider, remote: R) -> Self {
+        CachingProvider { cache, remote }
+    }
+}
+
+impl<R: InputProvider> InputProvider for CachingProvider<R> {
+    fn get_input(&self, year: u32, day: u32) -> Result<String, Box<dyn Error>> {
+        if self.cache.path(year, day).exists() {
+            return self.cache.get_input(year, day);
+        }
+        let input = self.remote.get_input(year, day)?;
+        self.cache.store(year, day, &input)?;
+        Ok(input)
+    }
+}
+
+pub fn manifest_dir() -> Result<PathBuf, Box<dyn Error>> {
+    // Fall back to the build time manifest dir so the binary works outside of cargo
+    let directory = match env::var_os("CARGO_MANIFEST_DIR") {
+        Some(directory) => directory
+            .into_string()
+            .map_err(|oss| -> String { format!("{:?}", oss) })?,
+        None => env!("CARGO_MANIFEST_DIR").to_owned(),
+    };
+    Ok(PathBuf::from(directory))
+}
+
+pub fn inputs_dir() -> Result<PathBuf, Box<dyn Error>> {
+    Ok(manifest_dir()?.join("inputs"))
+}
+
+// Names the cache directory of the current session so teammates don't share
+// inputs. AOC_ACCOUNT wins, otherwise the session token is hashed (FNV-1a) so
+// the token itself never ends up in a path.
+pub fn account() -> String {
+    if let Ok(account) = env::var("AOC_ACCOUNT") {
+        return account;
+    }
+    match env::var("AOC_SESSION") {
+        Ok(session) => {
+            let hash = session.bytes().fold(0xcbf29ce484222325u64, |hash, b| {
+                (hash ^ b as u64).wrapping_mul(0x100000001b3)
+            });
+            format!("session-{:08x}", hash as u32)
+        }
+        Err(_) => "default".to_owned(),
+    }
+}
+
+// Moves `inputs/day_<n>_input.txt` files into `inputs/<account>/2023/`.
+// Files that already exist in the new layout are left alone. Only run on
+// request through `aoc migrate`, so the account is the user's choice.
+pub fn migrate_flat_inputs(inputs: &Path, account: &str) -> Result<usize, Box<dyn Error>> {
+    if !inputs.is_dir() {
+        return Ok(0);
+    }
+    let target = FsProvider::new(inputs.join(account));
+    let mut moved = 0;
+    for entry in fs::read_dir(inputs)? {
+        let path = entry?.path();
+        let Some(day) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix("day_")?.strip_suffix("_input.txt"))
+            .and_then(|day| day.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let new_path = target.path(FLAT_LAYOUT_YEAR, day);
+        if new_path.exists() {
+            continue;
+        }
+        fs::create_dir_all(new_path.parent().unwrap())?;
+        fs::rename(&path, &new_path)?;
+        moved += 1;
+    }
+    Ok(moved)
+}
+
+pub fn default_provider() -> Result<impl InputProvider, Box<dyn Error>> {
+    let inputs = inputs_dir()?;
+    Ok(CachingProvider::new(
+        FsProvider::new(inputs.join(account())),
+        HttpProvider::from_env(),
+    ))
+}
+
+// Local stand-in for the puzzle server, shared by the tests of every module
+// that talks HTTP
+#[cfg(test)]
+pub(crate) mod mock {
+    use std::{
+        io::{Read, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use super::*;
+
+    // Answers one request per `(status, body)` pair and hands back the raw requests
+    pub(crate) fn mock_server(
+        responses: &[(u16, &'static str)],
+    ) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let responses = responses.to_vec();
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0; 1024];
+                loop {
+                    let n = stream.read(&mut buffer).unwrap();
+                    request.extend_from_slice(&buffer[..n]);
+                    let text = String::from_utf8_lossy(&request);
+                    let Some(header_end) = text.find("\r\n\r\n") else {
+                        if n == 0 {
+                            break;
+                        }
+                        continue;
+                    };
+                    let content_length = text[..header_end]
+                        .lines()
+                        .find_map(|l| l.strip_prefix("content-length: "))
+                        .map_or(0, |l| l.parse::<usize>().unwrap());
+                    if n == 0 || request.len() >= header_end + 4 + content_length {
+                        break;
+                    }
+                }
+                let response = format!(
+                    "HTTP/1.1 {status} X\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                );
+                stream.write_all(response.as_bytes()).unwrap();
+                requests.push(String::from_utf8(request).unwrap());
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+    pub(crate) fn mock_provider(url: &str) -> HttpProvider {
+        HttpProvider::new(url, Some("abc".to_owned())).with_min_interval(Duration::ZERO)
+    }
+
+    pub(crate) fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-{name}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mock::*, *};
+
+    #[test]
+    fn memory_provider() {
+        let provider = MemoryProvider::default().with(2023, 1, "1abc2");
+        assert_eq!(provider.get_input(2023, 1).unwrap(), "1abc2");
+        assert!(provider.get_input(2023, 2).is_err());
+    }
+
+    #[test]
+    fn http_provider_fetches_from_base_url() {
+        let (url, server) = mock_server(&[(200, "0 3 6 9\n")]);
+        let provider = mock_provider(&url);
+        assert_eq!(provider.get_input(2023, 9).unwrap(), "0 3 6 9\n");
+
+        let request = &server.join().unwrap()[0];
+        assert!(request.starts_with("GET /2023/day/9/input HTTP/1.1\r\n"));
+        assert!(request.contains("cookie: session=abc\r\n"));
+        assert!(request.contains(&format!("user-agent: {USER_AGENT}\r\n")));
+    }
+
+    #[test]
+    fn classify_responses() {
+        assert_eq!(classify(200, "1 2 3\n"), Ok(()));
+        assert_eq!(
+            classify(
+                400,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input."
+            ),
+            Err(FetchError::NotLoggedIn)
+        );
+        assert_eq!(
+            classify(
+                404,
+                "Please don't repeatedly request this endpoint before it unlocks!"
+            ),
+            Err(FetchError::TooEarly)
+        );
+        assert_eq!(classify(404, "404 Not Found"), Err(FetchError::NotFound));
+        assert_eq!(classify(500, ""), Err(FetchError::Server(500)));
+        assert_eq!(classify(302, ""), Err(FetchError::Status(302)));
+    }
+
+    #[test]
+    fn failed_fetch_is_not_cached() {
+        let dir = temp_dir("failed");
+        let (url, server) = mock_server(&[(500, "Internal Server Error"), (200, "ok")]);
+        let provider = CachingProvider::new(FsProvider::new(&dir), mock_provider(&url));
+        let err = provider.get_input(2023, 3).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<FetchError>(),
+            Some(&FetchError::Server(500))
+        );
+        assert!(!FsProvider::new(&dir).path(2023, 3).exists());
+
+        assert_eq!(provider.get_input(2023, 3).unwrap(), "ok");
+        server.join().unwrap();
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn requests_are_spaced_out() {
+        let (url, server) = mock_server(&[(200, "a"), (200, "b")]);
+        let interval = Duration::from_millis(200);
+        let provider = mock_provider(&url).with_min_interval(interval);
+        let start = Instant::now();
+        provider.fetch(2023, 1).unwrap();
+        provider.fetch(2023, 2).unwrap();
+        assert!(start.elapsed() >= interval);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn http_provider_needs_session() {
+        let provider = HttpProvider::new("http://127.0.0.1:1", None);
+        assert_eq!(provider.fetch(2023, 1), Err(FetchError::MissingSession));
+    }
+
+    #[test]
+    fn caching_provider_stores_fetched_input() {
+        let dir = temp_dir("cache");
+        let remote = MemoryProvider::default().with(2023, 4, "Card 1: 1 | 1");
+        let provider = CachingProvider::new(FsProvider::new(&dir), remote);
+        assert_eq!(provider.get_input(2023, 4).unwrap(), "Card 1: 1 | 1");
+        assert!(provider.get_input(2023, 5).is_err());
+
+        let cache = FsProvider::new(&dir);
+        assert_eq!(cache.get_input(2023, 4).unwrap(), "Card 1: 1 | 1");
+        assert!(dir.join("2023/day_4.txt").exists());
+        assert!(!cache.path(2023, 5).exists());
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn migrate_flat_layout() {
+        let dir = temp_dir("migrate");
+        fs::create_dir_all(dir.join("alice/2023")).unwrap();
+        fs::write(dir.join("day_1_input.txt"), "old 1").unwrap();
+        fs::write(dir.join("day_12_input.txt"), "old 12").unwrap();
+        fs::write(dir.join("alice/2023/day_12.txt"), "new 12").unwrap();
+        fs::write(dir.join("notes.txt"), "").unwrap();
+
+        assert_eq!(migrate_flat_inputs(&dir, "alice").unwrap(), 1);
+        let cache = FsProvider::new(dir.join("alice"));
+        assert_eq!(cache.get_input(2023, 1).unwrap(), "old 1");
+        assert_eq!(cache.get_input(2023, 12).unwrap(), "new 12");
+        assert!(!dir.join("day_1_input.txt").exists());
+        assert!(dir.join("notes.txt").exists());
+
+        assert_eq!(migrate_flat_inputs(&dir, "alice").unwrap(), 0);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -1,6 +1,10 @@
 #![allow(unused_imports)]
 #![allow(dead_code)]
+#![allow(clippy::needless_range_loop)]
+#![allow(clippy::only_used_in_recursion)]
 
+mod answers;
+mod bench;
 mod day1;
 mod day10;
 mod day11;
@@ -24,49 +28,514 @@ mod day6;
 mod day7;
 mod day8;
 mod day9;
+mod error;
+mod geometry;
+mod grid;
+mod input;
+mod search;
+mod solution;
+mod submit;
 
-use reqwest::{cookie::Jar, Url};
+use answers::{AnswerStore, Check};
+use input::InputProvider;
+use solution::{find_day, Answer, Day, Part};
 use std::{
     env,
     error::Error,
-    fs::{read_to_string, File},
-    io::Write,
-    path::Path,
+    fs::read_to_string,
+    io::{self, Read},
+    path::PathBuf,
 };
+use submit::Verdict;
+
+const YEAR: u32 = 2023;
+const DAYS: u32 = 23;
+
+const USAGE: &str = r#"Usage:
+    aoc run --day <n> [--part <1|2>] [--input <path>|-] [--param <name>=<value>]...
+    aoc run --day <n> --part <1|2> --submit
+    aoc run --all
+    aoc verify [--day <n>] [--part <1|2>]
+    aoc bench [--day <n>] [--iterations <n>] [--output <path>]
+    aoc export --day <n> [--input <path>|-] --output <dir>
+    aoc migrate --account <name>"#;
+
+const BENCH_ITERATIONS: usize = 10;
+const BENCH_OUTPUT: &str = "bench.json";
 
 pub fn get_input(year: u32, day: u32) -> Result<String, Box<dyn Error>> {
-    let directory = env::var_os("CARGO_MANIFEST_DIR").ok_or("CARGO_MANIFEST_DIR".to_string())?;
-    let directory = directory
-        .into_string()
-        .map_err(|oss| -> String { format!("{:?}", oss).into() })?;
-    let day_file = format!("{}/inputs/day_{day}_input.txt", directory);
-    let p = Path::new(&day_file);
-    if p.exists() {
-        Ok(read_to_string(p)?)
-    } else {
-        let s = fetch_todays_input(year, day)?;
-        let mut buffer = File::create(day_file)?;
-        buffer.write_all(s.as_bytes())?;
-        Ok(s)
+    input::default_provider()?.get_input(year, day)
+}
+
+#[derive(Debug, Default)]
+struct RunArgs {
+    day: Option<u32>,
+    part: Option<Part>,
+    input: Option<String>,
+    params: Vec<(String, i64)>,
+    all: bool,
+    submit: bool,
+    iterations: Option<usize>,
+    output: Option<String>,
+    account: Option<String>,
+}
+
+#[derive(Debug)]
+enum Command {
+    Run(RunArgs),
+    Verify(RunArgs),
+    Bench(RunArgs),
+    Export(RunArgs),
+    Migrate(String),
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Command, Box<dyn Error>> {
+    let command = args.next().ok_or(USAGE)?;
+    let run_args = parse_run_args(args)?;
+    match command.as_str() {
+        "run" => {
+            if run_args.account.is_some() {
+                return Err(format!("--account is for migrate\n{USAGE}").into());
+            }
+            if run_args.iterations.is_some() || run_args.output.is_some() {
+                return Err(
+                    format!("--iterations and --output are for bench and export\n{USAGE}").into(),
+                );
+            }
+            if !run_args.all && run_args.day.is_none() {
+                return Err(format!("missing --day or --all\n{USAGE}").into());
+            }
+            Ok(Command::Run(run_args))
+        }
+        "verify" => {
+            if run_args.all
+                || run_args.input.is_some()
+                || !run_args.params.is_empty()
+                || run_args.submit
+                || run_args.iterations.is_some()
+                || run_args.output.is_some()
+                || run_args.account.is_some()
+            {
+                return Err(format!("verify only takes --day and --part\n{USAGE}").into());
+            }
+            Ok(Command::Verify(run_args))
+        }
+        "bench" => {
+            if run_args.all
+                || run_args.part.is_some()
+                || run_args.input.is_some()
+                || !run_args.params.is_empty()
+                || run_args.submit
+                || run_args.account.is_some()
+            {
+                return Err(
+                    format!("bench only takes --day, --iterations and --output\n{USAGE}").into(),
+                );
+            }
+            if run_args.iterations == Some(0) {
+                return Err("--iterations must be at least 1".into());
+            }
+            Ok(Command::Bench(run_args))
+        }
+        "export" => {
+            if run_args.all
+                || run_args.part.is_some()
+                || !run_args.params.is_empty()
+                || run_args.submit
+                || run_args.iterations.is_some()
+                || run_args.account.is_some()
+            {
+                return Err(
+                    format!("export only takes --day, --input and --output\n{USAGE}").into(),
+                );
+            }
+            if run_args.day.is_none() || run_args.output.is_none() {
+                return Err(format!("export needs --day and --output\n{USAGE}").into());
+            }
+            Ok(Command::Export(run_args))
+        }
+        "migrate" => {
+            if run_args.day.is_some()
+                || run_args.part.is_some()
+                || run_args.all
+                || run_args.input.is_some()
+                || !run_args.params.is_empty()
+                || run_args.submit
+                || run_args.iterations.is_some()
+                || run_args.output.is_some()
+            {
+                return Err(format!("migrate only takes --account\n{USAGE}").into());
+            }
+            let account = run_args
+                .account
+                .ok_or(format!("missing --account\n{USAGE}"))?;
+            Ok(Command::Migrate(account))
+        }
+        _ => Err(format!("unknown command '{command}'\n{USAGE}").into()),
     }
 }
 
-fn fetch_todays_input(year: u32, day: u32) -> Result<String, Box<dyn Error>> {
-    let aoc_session = env::var_os("AOC_SESSION").ok_or("Missing AOC Session token".to_string())?;
-    let cookie = format!("session={:?}; Domain=.adventofcode.com", aoc_session);
-    let cookie_url = "https://adventofcode.com".parse::<Url>()?;
-    let jar = Jar::default();
-    jar.add_cookie_str(&cookie, &cookie_url);
+fn parse_run_args(mut args: impl Iterator<Item = String>) -> Result<RunArgs, Box<dyn Error>> {
+    let mut run_args = RunArgs::default();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or(format!("missing value for {arg}"));
+        match arg.as_str() {
+            "--day" => run_args.day = Some(value()?.parse()?),
+            "--part" => run_args.part = Some(value()?.parse()?),
+            "--input" => run_args.input = Some(value()?),
+            "--param" => {
+                let param = value()?;
+                let (name, value) = param
+                    .split_once('=')
+                    .ok_or(format!("expected <name>=<value>, got '{param}'"))?;
+                run_args.params.push((name.to_owned(), value.parse()?));
+            }
+            "--all" => run_args.all = true,
+            "--submit" => run_args.submit = true,
+            "--iterations" => run_args.iterations = Some(value()?.parse()?),
+            "--output" => run_args.output = Some(value()?),
+            "--account" => run_args.account = Some(value()?),
+            _ => return Err(format!("unknown argument '{arg}'\n{USAGE}").into()),
+        }
+    }
+
+    if run_args.all
+        && (run_args.day.is_some() || run_args.input.is_some() || !run_args.params.is_empty())
+    {
+        return Err("--all can not be combined with --day, --input or --param".into());
+    }
+    if run_args.submit && (run_args.all || run_args.input.is_some() || !run_args.params.is_empty())
+    {
+        return Err("--submit only works for a single day with the real input".into());
+    }
+    // A wrong answer locks the puzzle for a while, so each part is sent on its own
+    if run_args.submit && run_args.part.is_none() {
+        return Err("--submit needs --part".into());
+    }
+    Ok(run_args)
+}
 
-    let url = format!("https://adventofcode.com/{year}/day/{day}/input");
+fn read_input(day: u32, input: Option<&str>) -> Result<String, Box<dyn Error>> {
+    match input {
+        Some("-") => {
+            let mut buffer = String::new();
+            io::stdin().read_to_string(&mut buffer)?;
+            Ok(buffer)
+        }
+        Some(path) => Ok(read_to_string(path)?),
+        None => get_input(YEAR, day),
+    }
+}
 
-    let client = reqwest::blocking::Client::builder()
-        .cookie_store(true)
-        .cookie_provider(jar.into())
-        .build()?;
+fn solve(
+    day: u32,
+    parts: &[Part],
+    input: &str,
+    overrides: &[(String, i64)],
+) -> Result<Vec<Answer>, Box<dyn Error>> {
+    let day = find_day(day).ok_or(format!("day {day} is not implemented"))?;
+    solve_day(day, parts, input, overrides)
+}
 
-    let res = client.get(url).send()?.bytes()?;
-    Ok(String::from_utf8_lossy(&res).to_string())
+fn solve_day(
+    day: &Day,
+    parts: &[Part],
+    input: &str,
+    overrides: &[(String, i64)],
+) -> Result<Vec<Answer>, Box<dyn Error>> {
+    let mut params = day.solution.params();
+    for (name, value) in overrides {
+        params.set(name, *value)?;
+    }
+    let parsed = day.solution.parse_input(input)?;
+    for warning in day.solution.warnings(parsed.as_ref()) {
+        eprintln!("warning: {warning}");
+    }
+    Ok(parts
+        .iter()
+        .map(|part| day.solution.solve(*part, parsed.as_ref(), &params))
+        .collect())
 }
 
-fn main() {}
+fn run(args: RunArgs) -> Result<(), Box<dyn Error>> {
+    let parts = match args.part {
+        Some(part) => vec![part],
+        None => Part::ALL.to_vec(),
+    };
+
+    if args.all {
+        for day in 1..=DAYS {
+            let input = read_input(day, None)?;
+            for (part, answer) in parts.iter().zip(solve(day, &parts, &input, &[])?) {
+                println!("day {day} part {part}: {answer}");
+            }
+        }
+        return Ok(());
+    }
+
+    let day = args.day.ok_or("missing --day")?;
+    let input = read_input(day, args.input.as_deref())?;
+    for (part, answer) in parts.iter().zip(solve(day, &parts, &input, &args.params)?) {
+        if answer == Answer::Unsolved {
+            return Err(format!("day {day} part {part} is not implemented").into());
+        }
+        println!("{answer}");
+        if args.submit {
+            let verdict = submit::submit(YEAR, day, *part, &answer)?;
+            println!("{verdict}");
+            if verdict == Verdict::Correct {
+                let mut store = AnswerStore::for_year(YEAR)?;
+                store.accept(day, *part, &answer);
+                store.save()?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Compares the answers for the real inputs against the answer store, unknown
+// answers are recorded as pending
+fn verify(args: RunArgs) -> Result<(), Box<dyn Error>> {
+    let days = match args.day {
+        Some(day) => day..=day,
+        None => 1..=DAYS,
+    };
+    let parts = match args.part {
+        Some(part) => vec![part],
+        None => Part::ALL.to_vec(),
+    };
+
+    let mut store = AnswerStore::for_year(YEAR)?;
+    let mut mismatches = 0;
+    for day in days {
+        let input = read_input(day, None)?;
+        for (part, answer) in parts.iter().zip(solve(day, &parts, &input, &[])?) {
+            match store.check(day, *part, &answer) {
+                Check::Correct => println!("day {day} part {part}: ok"),
+                Check::Pending => println!("day {day} part {part}: pending, got {answer}"),
+                Check::Mismatch { expected } => {
+                    mismatches += 1;
+                    println!("day {day} part {part}: MISMATCH, expected {expected} got {answer}");
+                }
+            }
+        }
+    }
+    store.save()?;
+
+    if mismatches > 0 {
+        return Err(format!("{mismatches} answers do not match the answer store").into());
+    }
+    Ok(())
+}
+
+fn bench(args: RunArgs) -> Result<(), Box<dyn Error>> {
+    let days = match args.day {
+        Some(day) => day..=day,
+        None => 1..=DAYS,
+    };
+    let iterations = args.iterations.unwrap_or(BENCH_ITERATIONS);
+
+    let mut timings = Vec::new();
+    for day in days {
+        let solution = find_day(day).ok_or(format!("day {day} is not implemented"))?;
+        let input = read_input(day, None)?;
+        let day_timings = bench::bench_day(solution, &input, iterations)?;
+        for (phase, stats) in day_timings.phases() {
+            println!(
+                "day {day:>2} {phase}: min {:?}, median {:?}, max {:?}",
+                stats.min, stats.median, stats.max
+            );
+        }
+        timings.push(day_timings);
+    }
+
+    // Timings depend on the machine, so the default report is not tracked
+    let output = match args.output {
+        Some(output) => PathBuf::from(output),
+        None => input::manifest_dir()?.join(BENCH_OUTPUT),
+    };
+    std::fs::write(&output, bench::to_json(iterations, &timings))?;
+    println!("report written to {}", output.display());
+    Ok(())
+}
+
+// Writes the files a day can export for its input into the output directory
+fn export(args: RunArgs) -> Result<(), Box<dyn Error>> {
+    let day = args.day.ok_or("missing --day")?;
+    let output = PathBuf::from(args.output.ok_or("missing --output")?);
+    let solution = find_day(day).ok_or(format!("day {day} is not implemented"))?;
+    let input = read_input(day, args.input.as_deref())?;
+    let parsed = solution.solution.parse_input(&input)?;
+    let files = solution.solution.exports(parsed.as_ref());
+    if files.is_empty() {
+        return Err(format!("day {day} has nothing to export").into());
+    }
+
+    std::fs::create_dir_all(&output)?;
+    for (name, contents) in files {
+        let path = output.join(name);
+        std::fs::write(&path, contents)?;
+        println!("written {}", path.display());
+    }
+    Ok(())
+}
+
+// Moves inputs cached in the old flat layout into the given account
+fn migrate(account: &str) -> Result<(), Box<dyn Error>> {
+    let moved = input::migrate_flat_inputs(&input::inputs_dir()?, account)?;
+    println!("moved {moved} inputs to inputs/{account}/{YEAR}");
+    Ok(())
+}
+
+fn main() {
+    let result = parse_args(env::args().skip(1)).and_then(|command| match command {
+        Command::Run(args) => run(args),
+        Command::Verify(args) => verify(args),
+        Command::Bench(args) => bench(args),
+        Command::Export(args) => export(args),
+        Command::Migrate(account) => migrate(&account),
+    });
+    if let Err(e) = result {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{error::AocResult, solution::Params, solution::Solution};
+
+    use super::*;
+
+    fn args(args: &str) -> impl Iterator<Item = String> + '_ {
+        args.split_whitespace().map(str::to_owned)
+    }
+
+    fn parse_run(line: &str) -> RunArgs {
+        match parse_args(args(line)).unwrap() {
+            Command::Run(run_args) => run_args,
+            command => panic!("expected a run command, got {command:?}"),
+        }
+    }
+
+    #[test]
+    fn parse_run_args() {
+        let run_args = parse_run("run --day 17 --part 2 --input -");
+        assert_eq!(run_args.day, Some(17));
+        assert_eq!(run_args.part, Some(Part::Two));
+        assert_eq!(run_args.input.as_deref(), Some("-"));
+        assert!(!run_args.all);
+
+        let run_args = parse_run("run --day 21 --param steps=6");
+        assert_eq!(run_args.params, [("steps".to_owned(), 6)]);
+
+        let all_args = parse_run("run --all");
+        assert!(all_args.all);
+
+        assert!(parse_args(args("run")).is_err());
+        assert!(parse_args(args("run --day 1 --part 3")).is_err());
+        assert!(parse_args(args("run --all --day 1")).is_err());
+        assert!(parse_args(args("run --day 21 --param steps")).is_err());
+        assert!(parse_run("run --day 1 --part 2 --submit").submit);
+        assert!(parse_args(args("run --day 1 --submit")).is_err());
+        assert!(parse_args(args("run --day 1 --input - --submit")).is_err());
+        assert!(parse_args(args("fetch --day 1")).is_err());
+    }
+
+    #[test]
+    fn parse_verify_args() {
+        let Command::Verify(verify_args) = parse_args(args("verify --day 3")).unwrap() else {
+            panic!("expected a verify command");
+        };
+        assert_eq!(verify_args.day, Some(3));
+        assert!(matches!(
+            parse_args(args("verify")).unwrap(),
+            Command::Verify(_)
+        ));
+        assert!(parse_args(args("verify --input -")).is_err());
+    }
+
+    #[test]
+    fn parse_bench_args() {
+        let Command::Bench(bench_args) =
+            parse_args(args("bench --day 5 --iterations 3 --output a.json")).unwrap()
+        else {
+            panic!("expected a bench command");
+        };
+        assert_eq!(bench_args.day, Some(5));
+        assert_eq!(bench_args.iterations, Some(3));
+        assert_eq!(bench_args.output.as_deref(), Some("a.json"));
+        assert!(parse_args(args("bench --iterations 0")).is_err());
+        assert!(parse_args(args("bench --part 1")).is_err());
+        assert!(parse_args(args("run --day 1 --iterations 3")).is_err());
+    }
+
+    #[test]
+    fn parse_export_args() {
+        let Command::Export(export_args) =
+            parse_args(args("export --day 19 --input - --output out")).unwrap()
+        else {
+            panic!("expected an export command");
+        };
+        assert_eq!(export_args.day, Some(19));
+        assert_eq!(export_args.input.as_deref(), Some("-"));
+        assert_eq!(export_args.output.as_deref(), Some("out"));
+        assert!(parse_args(args("export --day 19")).is_err());
+        assert!(parse_args(args("export --output out")).is_err());
+        assert!(parse_args(args("export --day 19 --part 1 --output out")).is_err());
+    }
+
+    #[test]
+    fn parse_migrate_args() {
+        let Command::Migrate(account) = parse_args(args("migrate --account alice")).unwrap() else {
+            panic!("expected a migrate command");
+        };
+        assert_eq!(account, "alice");
+        assert!(parse_args(args("migrate")).is_err());
+        assert!(parse_args(args("migrate --account alice --day 1")).is_err());
+        assert!(parse_args(args("run --day 1 --account alice")).is_err());
+    }
+
+    #[test]
+    fn solve_dispatch() {
+        let example = "1abc2\npqr3stu8vwx\na1b2c3d4e5f\ntreb7uchet";
+        assert_eq!(solve(1, &[Part::One], example, &[]).unwrap(), [142]);
+        assert!(solve(17, &[Part::Two], "", &[]).is_err());
+        assert!(solve(19, &[Part::One], "", &[]).is_err());
+        assert!(solve(24, &[Part::One], "", &[]).is_err());
+
+        let overrides = [("steps".to_owned(), 6)];
+        assert!(solve(1, &[Part::One], example, &overrides).is_err());
+    }
+
+    // A day whose parts are not implemented yet
+    struct NotImplemented;
+
+    impl Solution for NotImplemented {
+        type Input = ();
+
+        fn parse(_input: &str) -> AocResult<Self::Input> {
+            Ok(())
+        }
+
+        fn part1(_input: &Self::Input, _params: &Params) -> Answer {
+            Answer::Unsolved
+        }
+
+        fn part2(_input: &Self::Input, _params: &Params) -> Answer {
+            Answer::Unsolved
+        }
+    }
+
+    #[test]
+    fn solve_unsolved_part() {
+        let day = Day {
+            day: 25,
+            solution: &NotImplemented,
+        };
+        assert_eq!(
+            solve_day(&day, &Part::ALL, "", &[]).unwrap(),
+            [Answer::Unsolved, Answer::Unsolved]
+        );
+    }
+}
@@ -0,0 +1,531 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+// Anything the weighted searches can add up and compare
+pub trait Cost: Copy + Ord + Default + Add<Output = Self> {}
+
+impl<T: Copy + Ord + Default + Add<Output = T>> Cost for T {}
+
+// How much work a search did. A cache hit is a state that was reached again
+// and skipped because it was already known.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub expanded: usize,
+    pub cache_hits: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Found<S, C> {
+    pub cost: C,
+    // From the start to the goal, both included
+    pub path: Vec<S>,
+    pub stats: Stats,
+}
+
+// Finds the arena id of a state that was seen before
+trait StateIndex<S> {
+    fn get(&self, state: &S) -> Option<usize>;
+    fn insert(&mut self, state: &S, id: usize);
+}
+
+impl<S: Clone + Eq + Hash> StateIndex<S> for HashMap<S, usize> {
+    fn get(&self, state: &S) -> Option<usize> {
+        HashMap::get(self, state).copied()
+    }
+
+    fn insert(&mut self, state: &S, id: usize) {
+        HashMap::insert(self, state.clone(), id);
+    }
+}
+
+// For state spaces that can be numbered `0..len` up front, which saves
+// hashing every state
+struct DenseIndex<F> {
+    key: F,
+    ids: Vec<Option<usize>>,
+}
+
+impl<S, F: Fn(&S) -> usize> StateIndex<S> for DenseIndex<F> {
+    fn get(&self, state: &S) -> Option<usize> {
+        self.ids[(self.key)(state)]
+    }
+
+    fn insert(&mut self, state: &S, id: usize) {
+        self.ids[(self.key)(state)] = Some(id);
+    }
+}
+
+// Every state seen so far with the one it was reached from, so paths can be
+// rebuilt without storing them in the states
+struct Arena<S, X = HashMap<S, usize>> {
+    nodes: Vec<(S, Option<usize>)>,
+    index: X,
+}
+
+impl<S: Clone + Eq + Hash> Arena<S> {
+    fn new() -> Self {
+        Arena::with_index(HashMap::new())
+    }
+}
+
+impl<S: Clone, X: StateIndex<S>> Arena<S, X> {
+    fn with_index(index: X) -> Self {
+        Arena {
+            nodes: Vec::new(),
+            index,
+        }
+    }
+
+    fn get(&self, state: &S) -> Option<usize> {
+        self.index.get(state)
+    }
+
+    fn insert(&mut self, state: S, parent: Option<usize>) -> usize {
+        self.index.insert(&state, self.nodes.len());
+        self.push(state, parent)
+    }
+
+    // Without indexing, for searches that can hold the same state twice
+    fn push(&mut self, state: S, parent: Option<usize>) -> usize {
+        self.nodes.push((state, parent));
+        self.nodes.len() - 1
+    }
+
+    fn path(&self, mut id: usize) -> Vec<S> {
+        let mut path = vec![self.nodes[id].0.clone()];
+        while let Some(parent) = self.nodes[id].1 {
+            path.push(self.nodes[parent].0.clone());
+            id = parent;
+        }
+        path.reverse();
+        path
+    }
+}
+
+// Cheapest path to a goal, `successors` gives the next states with the cost
+// of moving there
+pub fn dijkstra<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(starts, successors, |_| C::default(), is_goal)
+}
+
+// `dijkstra` for states that `key` numbers from 0 up to `len`
+pub fn dijkstra_dense<S, C, I>(
+    len: usize,
+    key: impl Fn(&S) -> usize,
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let index = DenseIndex {
+        key,
+        ids: vec![None; len],
+    };
+    best_first(
+        Arena::with_index(index),
+        starts,
+        successors,
+        |_| C::default(),
+        is_goal,
+    )
+}
+
+// Like `dijkstra`, but states closer to the goal according to `heuristic`
+// are tried first. The heuristic must never overestimate the remaining cost.
+pub fn astar<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> C,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    best_first(Arena::new(), starts, successors, heuristic, is_goal)
+}
+
+fn best_first<S, C, I, X>(
+    mut arena: Arena<S, X>,
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+    X: StateIndex<S>,
+{
+    let mut costs = Vec::new();
+    let mut queue = BinaryHeap::new();
+    let mut stats = Stats::default();
+    for start in starts {
+        if arena.get(&start).is_some() {
+            continue;
+        }
+        let estimate = heuristic(&start);
+        let id = arena.insert(start, None);
+        costs.push(C::default());
+        queue.push(Reverse((estimate, C::default(), id)));
+    }
+
+    while let Some(Reverse((_, cost, id))) = queue.pop() {
+        // A cheaper way here was found after this entry was queued
+        if cost > costs[id] {
+            stats.cache_hits += 1;
+            continue;
+        }
+        let state = arena.nodes[id].0.clone();
+        if is_goal(&state) {
+            return Some(Found {
+                cost,
+                path: arena.path(id),
+                stats,
+            });
+        }
+        stats.expanded += 1;
+
+        for (next, step_cost) in successors(&state) {
+            let next_cost = cost + step_cost;
+            let next_id = match arena.get(&next) {
+                Some(next_id) if costs[next_id] <= next_cost => {
+                    stats.cache_hits += 1;
+                    continue;
+                }
+                Some(next_id) => {
+                    costs[next_id] = next_cost;
+                    arena.nodes[next_id].1 = Some(id);
+                    next_id
+                }
+                None => {
+                    costs.push(next_cost);
+                    arena.insert(next.clone(), Some(id))
+                }
+            };
+            let estimate = next_cost + heuristic(&next);
+            queue.push(Reverse((estimate, next_cost, next_id)));
+        }
+    }
+    None
+}
+
+// Shortest path to a goal counted in steps
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut arena = Arena::new();
+    let mut queue = VecDeque::new();
+    let mut stats = Stats::default();
+    for start in starts {
+        if arena.get(&start).is_none() {
+            queue.push_back((arena.insert(start, None), 0));
+        }
+    }
+
+    while let Some((id, steps)) = queue.pop_front() {
+        let state = arena.nodes[id].0.clone();
+        if is_goal(&state) {
+            return Some(Found {
+                cost: steps,
+                path: arena.path(id),
+                stats,
+            });
+        }
+        stats.expanded += 1;
+
+        for next in successors(&state) {
+            if arena.get(&next).is_some() {
+                stats.cache_hits += 1;
+                continue;
+            }
+            queue.push_back((arena.insert(next, Some(id)), steps + 1));
+        }
+    }
+    None
+}
+
+#[derive(Debug, Clone)]
+pub struct Reach<S> {
+    // The fewest steps every reached state is away from the closest start
+    pub distances: HashMap<S, usize>,
+    pub stats: Stats,
+}
+
+// Everything `bfs` can reach in at most `max_steps` steps
+pub fn bfs_reach<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    max_steps: usize,
+    mut successors: impl FnMut(&S) -> I,
+) -> Reach<S>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut distances = HashMap::new();
+    let mut queue = VecDeque::new();
+    let mut stats = Stats::default();
+    for start in starts {
+        if distances.insert(start.clone(), 0).is_none() {
+            queue.push_back((start, 0));
+        }
+    }
+
+    while let Some((state, steps)) = queue.pop_front() {
+        if steps == max_steps {
+            continue;
+        }
+        stats.expanded += 1;
+
+        for next in successors(&state) {
+            if distances.contains_key(&next) {
+                stats.cache_hits += 1;
+                continue;
+            }
+            distances.insert(next.clone(), steps + 1);
+            queue.push_back((next, steps + 1));
+        }
+    }
+    Reach { distances, stats }
+}
+
+// Any path to a goal, going as deep as possible first. Every state is only
+// visited once, so the path found is not necessarily the shortest.
+pub fn dfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut arena = Arena::new();
+    let mut stack = Vec::new();
+    let mut visited = HashSet::new();
+    let mut stats = Stats::default();
+    for start in starts {
+        stack.push(arena.push(start, None));
+    }
+
+    while let Some(id) = stack.pop() {
+        let state = arena.nodes[id].0.clone();
+        if is_goal(&state) {
+            let path = arena.path(id);
+            return Some(Found {
+                cost: path.len() - 1,
+                path,
+                stats,
+            });
+        }
+        if !visited.insert(state.clone()) {
+            stats.cache_hits += 1;
+            continue;
+        }
+        stats.expanded += 1;
+
+        for next in successors(&state) {
+            if visited.contains(&next) {
+                stats.cache_hits += 1;
+                continue;
+            }
+            stack.push(arena.push(next, Some(id)));
+        }
+    }
+    None
+}
+
+// The most expensive path to a goal that never visits a state twice. Every
+// such path is tried by backtracking, so this is only feasible for small
+// state spaces. Goals are not searched any further.
+pub fn longest_path<S, C, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut stats = Stats::default();
+    if is_goal(&start) {
+        return Some(Found {
+            cost: C::default(),
+            path: vec![start],
+            stats,
+        });
+    }
+
+    let mut best: Option<(C, Vec<S>)> = None;
+    let mut on_path = HashSet::from([start.clone()]);
+    let first = successors(&start).into_iter().collect::<Vec<_>>();
+    stats.expanded += 1;
+    let mut frames = vec![(start, C::default(), first.into_iter())];
+    while let Some((_, cost, next_states)) = frames.last_mut() {
+        let cost = *cost;
+        let Some((next, step_cost)) = next_states.next() else {
+            let (state, ..) = frames.pop().unwrap();
+            on_path.remove(&state);
+            continue;
+        };
+        if on_path.contains(&next) {
+            stats.cache_hits += 1;
+            continue;
+        }
+
+        let next_cost = cost + step_cost;
+        if is_goal(&next) {
+            if best.as_ref().is_none_or(|(best, _)| next_cost > *best) {
+                let mut path = frames
+                    .iter()
+                    .map(|(state, ..)| state.clone())
+                    .collect::<Vec<_>>();
+                path.push(next);
+                best = Some((next_cost, path));
+            }
+            continue;
+        }
+
+        stats.expanded += 1;
+        let next_states = successors(&next).into_iter().collect::<Vec<_>>();
+        on_path.insert(next.clone());
+        frames.push((next, next_cost, next_states.into_iter()));
+    }
+
+    best.map(|(cost, path)| Found { cost, path, stats })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Point;
+    use crate::grid::{Grid, Pos};
+
+    const MAZE: &str = "S.#...\n.##.#.\n...#..\n.#...G";
+
+    fn open_neighbours(grid: &Grid<u8>, pos: Pos) -> Vec<Pos> {
+        grid.neighbours4(pos)
+            .filter(|&next| grid[next] != b'#')
+            .collect()
+    }
+
+    #[test]
+    fn shortest_paths() {
+        let grid = Grid::parse(MAZE, b".#SG").unwrap();
+        let start = Point::new(0, 0);
+        let goal = Point::new(5, 3);
+
+        let found = bfs(
+            [start],
+            |&pos| open_neighbours(&grid, pos),
+            |&pos| pos == goal,
+        )
+        .unwrap();
+        assert_eq!(found.cost, 8);
+        assert_eq!(found.path.len(), 9);
+        assert_eq!((found.path[0], found.path[8]), (start, goal));
+        assert!(found
+            .path
+            .windows(2)
+            .all(|step| step[0].manhattan(step[1]) == 1));
+
+        let weighted = |&pos: &Pos| {
+            open_neighbours(&grid, pos)
+                .into_iter()
+                .map(|next| (next, 2))
+                .collect::<Vec<_>>()
+        };
+        let found = dijkstra([start], weighted, |&pos| pos == goal).unwrap();
+        assert_eq!(found.cost, 16);
+        let guided = astar(
+            [start],
+            weighted,
+            |&pos| 2 * pos.manhattan(goal),
+            |&pos| pos == goal,
+        )
+        .unwrap();
+        assert_eq!(guided.cost, 16);
+        assert!(guided.stats.expanded <= found.stats.expanded);
+
+        // Only reachable by going all the way around
+        let far = Point::new(3, 0);
+        assert_eq!(
+            bfs(
+                [start],
+                |&pos| open_neighbours(&grid, pos),
+                |&pos| pos == far
+            )
+            .map(|found| found.cost),
+            Some(13)
+        );
+        assert!(bfs([start], |&pos| open_neighbours(&grid, pos), |_| false).is_none());
+    }
+
+    #[test]
+    fn reach_and_dfs() {
+        let grid = Grid::parse(MAZE, b".#SG").unwrap();
+        let start = Point::new(0, 0);
+
+        let reach = bfs_reach([start], 3, |&pos| open_neighbours(&grid, pos));
+        assert_eq!(reach.distances.len(), 6);
+        assert_eq!(reach.distances[&Point::new(1, 2)], 3);
+
+        let found = dfs(
+            [start],
+            |&pos| open_neighbours(&grid, pos),
+            |&pos| pos.y == 3,
+        )
+        .unwrap();
+        assert_eq!(found.path.len(), found.cost + 1);
+        assert_eq!(found.path.last().unwrap().y, 3);
+        assert!(dfs([start], |&pos| open_neighbours(&grid, pos), |_| false).is_none());
+    }
+
+    #[test]
+    fn longest_simple_path() {
+        // Down the left edge and up the middle column is longer than going
+        // straight through
+        let grid = Grid::parse("S....\n.#.#.\n....G", b".#SG").unwrap();
+        let goal = Point::new(4, 2);
+        let found = longest_path(
+            Point::new(0, 0),
+            |&pos| {
+                open_neighbours(&grid, pos)
+                    .into_iter()
+                    .map(|next| (next, 1))
+                    .collect::<Vec<_>>()
+            },
+            |&pos| pos == goal,
+        )
+        .unwrap();
+        assert_eq!(found.cost, 10);
+        assert_eq!(found.path.len(), 11);
+        assert_eq!(found.path.iter().collect::<HashSet<_>>().len(), 11);
+    }
+}
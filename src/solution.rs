@@ -0,0 +1,451 @@
+use std::{any::Any, collections::HashMap, fmt::Display, ops::RangeInclusive, str::FromStr};
+
+use crate::{
+    day1, day10, day11, day12, day13, day14, day15, day16, day17, day18, day19, day2, day20, day21,
+    day22, day23, day3, day4, day5, day6, day7, day8, day9, error::AocResult,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Signed(i64),
+    Unsigned(u64),
+    Text(String),
+    Unsolved,
+}
+
+impl Answer {
+    fn as_i128(&self) -> Option<i128> {
+        match self {
+            Answer::Signed(v) => Some(*v as i128),
+            Answer::Unsigned(v) => Some(*v as i128),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Answer::Signed(v) => write!(f, "{v}"),
+            Answer::Unsigned(v) => write!(f, "{v}"),
+            Answer::Text(s) => write!(f, "{s}"),
+            Answer::Unsolved => write!(f, "unsolved"),
+        }
+    }
+}
+
+macro_rules! impl_answer_from {
+    ($variant:ident, $inner:ty, $($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::$variant(value as $inner)
+                }
+            }
+
+            impl PartialEq<$t> for Answer {
+                fn eq(&self, other: &$t) -> bool {
+                    self.as_i128() == Some(*other as i128)
+                }
+            }
+        )*
+    };
+}
+
+impl_answer_from!(Signed, i64, i32, i64);
+impl_answer_from!(Unsigned, u64, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Self {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Self {
+        Answer::Text(value.to_owned())
+    }
+}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        matches!(self, Answer::Text(s) if s == other)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("part must be 1 or 2, got {s}")),
+        }
+    }
+}
+
+// A tunable constant of a puzzle, e.g. the number of steps to walk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Param {
+    pub name: &'static str,
+    pub default: i64,
+    pub kind: ParamKind,
+}
+
+// The type a day reads a parameter as, which bounds the values it accepts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamKind {
+    // 0 or 1, read as `i64`
+    Flag,
+    I64,
+    U32,
+    // A `u32` of at least 1, e.g. a factor things are scaled by
+    PositiveU32,
+    Usize,
+}
+
+impl ParamKind {
+    fn range(self) -> RangeInclusive<i64> {
+        match self {
+            ParamKind::Flag => 0..=1,
+            ParamKind::I64 => i64::MIN..=i64::MAX,
+            ParamKind::U32 => 0..=u32::MAX as i64,
+            ParamKind::PositiveU32 => 1..=u32::MAX as i64,
+            ParamKind::Usize => 0..=i64::try_from(usize::MAX).unwrap_or(i64::MAX),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Params {
+    declared: &'static [Param],
+    overrides: HashMap<&'static str, i64>,
+}
+
+impl Params {
+    pub fn new(declared: &'static [Param]) -> Self {
+        Params {
+            declared,
+            overrides: HashMap::new(),
+        }
+    }
+
+    pub fn set(&mut self, name: &str, value: i64) -> Result<(), String> {
+        let param = self
+            .declared
+            .iter()
+            .find(|p| p.name == name)
+            .ok_or(format!("unknown parameter '{name}'"))?;
+        let range = param.kind.range();
+        if !range.contains(&value) {
+            return Err(format!(
+                "parameter '{name}' must be in {}..={}, got {value}",
+                range.start(),
+                range.end()
+            ));
+        }
+        self.overrides.insert(param.name, value);
+        Ok(())
+    }
+
+    pub fn with(mut self, name: &str, value: i64) -> Self {
+        self.set(name, value).unwrap();
+        self
+    }
+
+    pub fn get<T: TryFrom<i64>>(&self, name: &str) -> T {
+        let param = self
+            .declared
+            .iter()
+            .find(|p| p.name == name)
+            .unwrap_or_else(|| panic!("parameter '{name}' is not declared"));
+        let value = self.overrides.get(name).copied().unwrap_or(param.default);
+        T::try_from(value).unwrap_or_else(|_| panic!("parameter '{name}' is out of range: {value}"))
+    }
+}
+
+pub trait Solution {
+    type Input;
+
+    const PARAMS: &'static [Param] = &[];
+
+    fn parse(input: &str) -> AocResult<Self::Input>;
+    fn part1(input: &Self::Input, params: &Params) -> Answer;
+    fn part2(input: &Self::Input, params: &Params) -> Answer;
+
+    // Problems in the input that still allow an answer, the runner prints them
+    fn warnings(_input: &Self::Input) -> Vec<String> {
+        Vec::new()
+    }
+
+    // Files showing the input in other tools, by file name, written by the
+    // export command
+    fn exports(_input: &Self::Input) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
+    fn params() -> Params {
+        Params::new(Self::PARAMS)
+    }
+
+    fn solve_part1(input: &str) -> AocResult<Answer> {
+        Self::solve_part1_with(input, &Self::params())
+    }
+
+    fn solve_part2(input: &str) -> AocResult<Answer> {
+        Self::solve_part2_with(input, &Self::params())
+    }
+
+    fn solve_part1_with(input: &str, params: &Params) -> AocResult<Answer> {
+        Ok(Self::part1(&Self::parse(input)?, params))
+    }
+
+    fn solve_part2_with(input: &str, params: &Params) -> AocResult<Answer> {
+        Ok(Self::part2(&Self::parse(input)?, params))
+    }
+}
+
+// Object safe view of a `Solution` so every day can live in one registry
+pub trait DynSolution: Sync {
+    fn params(&self) -> Params;
+    fn parse_input(&self, input: &str) -> AocResult<Box<dyn Any>>;
+    fn solve(&self, part: Part, parsed: &dyn Any, params: &Params) -> Answer;
+    fn warnings(&self, parsed: &dyn Any) -> Vec<String>;
+    fn exports(&self, parsed: &dyn Any) -> Vec<(&'static str, String)>;
+}
+
+impl<S> DynSolution for S
+where
+    S: Solution + Sync,
+    S::Input: 'static,
+{
+    fn params(&self) -> Params {
+        S::params()
+    }
+
+    fn parse_input(&self, input: &str) -> AocResult<Box<dyn Any>> {
+        Ok(Box::new(S::parse(input)?))
+    }
+
+    fn solve(&self, part: Part, parsed: &dyn Any, params: &Params) -> Answer {
+        let parsed = parsed
+            .downcast_ref::<S::Input>()
+            .expect("parsed input does not belong to this solution");
+        match part {
+            Part::One => S::part1(parsed, params),
+            Part::Two => S::part2(parsed, params),
+        }
+    }
+
+    fn warnings(&self, parsed: &dyn Any) -> Vec<String> {
+        let parsed = parsed
+            .downcast_ref::<S::Input>()
+            .expect("parsed input does not belong to this solution");
+        S::warnings(parsed)
+    }
+
+    fn exports(&self, parsed: &dyn Any) -> Vec<(&'static str, String)> {
+        let parsed = parsed
+            .downcast_ref::<S::Input>()
+            .expect("parsed input does not belong to this solution");
+        S::exports(parsed)
+    }
+}
+
+pub struct Day {
+    pub day: u32,
+    pub solution: &'static dyn DynSolution,
+}
+
+impl Day {
+    pub fn run(&self, part: Part, input: &str, params: &Params) -> AocResult<Answer> {
+        let parsed = self.solution.parse_input(input)?;
+        Ok(self.solution.solve(part, parsed.as_ref(), params))
+    }
+}
+
+pub static DAYS: [Day; 23] = [
+    Day {
+        day: 1,
+        solution: &day1::Day1,
+    },
+    Day {
+        day: 2,
+        solution: &day2::Day2,
+    },
+    Day {
+        day: 3,
+        solution: &day3::Day3,
+    },
+    Day {
+        day: 4,
+        solution: &day4::Day4,
+    },
+    Day {
+        day: 5,
+        solution: &day5::Day5,
+    },
+    Day {
+        day: 6,
+        solution: &day6::Day6,
+    },
+    Day {
+        day: 7,
+        solution: &day7::Day7,
+    },
+    Day {
+        day: 8,
+        solution: &day8::Day8,
+    },
+    Day {
+        day: 9,
+        solution: &day9::Day9,
+    },
+    Day {
+        day: 10,
+        solution: &day10::Day10,
+    },
+    Day {
+        day: 11,
+        solution: &day11::Day11,
+    },
+    Day {
+        day: 12,
+        solution: &day12::Day12,
+    },
+    Day {
+        day: 13,
+        solution: &day13::Day13,
+    },
+    Day {
+        day: 14,
+        solution: &day14::Day14,
+    },
+    Day {
+        day: 15,
+        solution: &day15::Day15,
+    },
+    Day {
+        day: 16,
+        solution: &day16::Day16,
+    },
+    Day {
+        day: 17,
+        solution: &day17::Day17,
+    },
+    Day {
+        day: 18,
+        solution: &day18::Day18,
+    },
+    Day {
+        day: 19,
+        solution: &day19::Day19,
+    },
+    Day {
+        day: 20,
+        solution: &day20::Day20,
+    },
+    Day {
+        day: 21,
+        solution: &day21::Day21,
+    },
+    Day {
+        day: 22,
+        solution: &day22::Day22,
+    },
+    Day {
+        day: 23,
+        solution: &day23::Day23,
+    },
+];
+
+pub fn find_day(day: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answer_compares_numerically() {
+        assert_eq!(Answer::from(142), 142);
+        assert_eq!(Answer::from(142u64), 142);
+        assert_eq!(Answer::from(-3i64), -3);
+        assert_ne!(Answer::from(-3i64), 3u64);
+        assert_eq!(Answer::from("abc"), "abc");
+        assert_ne!(Answer::Unsolved, 0);
+        assert_eq!(Answer::from(7usize).to_string(), "7");
+    }
+
+    #[test]
+    fn registry_is_ordered() {
+        for (i, day) in DAYS.iter().enumerate() {
+            assert_eq!(day.day as usize, i + 1);
+        }
+        assert!(find_day(24).is_none());
+    }
+
+    #[test]
+    fn params_fall_back_to_defaults() {
+        const PARAMS: &[Param] = &[Param {
+            name: "steps",
+            default: 64,
+            kind: ParamKind::Usize,
+        }];
+        let mut params = Params::new(PARAMS);
+        assert_eq!(params.get::<usize>("steps"), 64);
+        params.set("steps", 6).unwrap();
+        assert_eq!(params.get::<usize>("steps"), 6);
+        assert!(params.set("loops", 1).is_err());
+    }
+
+    #[test]
+    fn params_are_range_checked() {
+        const PARAMS: &[Param] = &[
+            Param {
+                name: "red",
+                default: 12,
+                kind: ParamKind::U32,
+            },
+            Param {
+                name: "export",
+                default: 0,
+                kind: ParamKind::Flag,
+            },
+            Param {
+                name: "factor",
+                default: 2,
+                kind: ParamKind::PositiveU32,
+            },
+        ];
+        let mut params = Params::new(PARAMS);
+        assert!(params.set("red", -1).is_err());
+        assert!(params.set("red", 99999999999).is_err());
+        assert!(params.set("export", 2).is_err());
+        assert!(params.set("factor", 0).is_err());
+        assert!(params.set("factor", -3).is_err());
+        assert_eq!(params.get::<u32>("red"), 12);
+        params.set("red", u32::MAX as i64).unwrap();
+        assert_eq!(params.get::<u32>("red"), u32::MAX);
+    }
+}
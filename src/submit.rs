@@ -0,0 +1,260 @@
+use std::{
+    error::Error,
+    fmt::Display,
+    fs::{self, read_to_string, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    str::FromStr,
+    time::Duration,
+};
+
+use crate::{
+    input::{self, HttpProvider},
+    solution::{Answer, Part},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    Wrong,
+    RateLimited { wait: Duration },
+    AlreadySolved,
+}
+
+impl Verdict {
+    pub fn parse(html: &str) -> Option<Verdict> {
+        if html.contains("That's the right answer") {
+            Some(Verdict::Correct)
+        } else if html.contains("your answer is too high") {
+            Some(Verdict::TooHigh)
+        } else if html.contains("your answer is too low") {
+            Some(Verdict::TooLow)
+        } else if html.contains("That's not the right answer") {
+            Some(Verdict::Wrong)
+        } else if html.contains("You gave an answer too recently") {
+            Some(Verdict::RateLimited {
+                wait: parse_wait(html).unwrap_or_default(),
+            })
+        } else if html.contains("Did you already complete it") {
+            Some(Verdict::AlreadySolved)
+        } else {
+            None
+        }
+    }
+}
+
+// "You have 4m 32s left to wait." -> 272s
+fn parse_wait(html: &str) -> Option<Duration> {
+    let start = html.find("You have ")? + "You have ".len();
+    let end = start + html[start..].find(" left to wait")?;
+    let mut seconds = 0;
+    for amount in html[start..end].split_whitespace() {
+        let (value, unit) = amount.split_at(amount.len() - 1);
+        let value = value.parse::<u64>().ok()?;
+        seconds += match unit {
+            "h" => value * 3600,
+            "m" => value * 60,
+            "s" => value,
+            _ => return None,
+        };
+    }
+    Some(Duration::from_secs(seconds))
+}
+
+impl Display for Verdict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Verdict::Correct => write!(f, "correct"),
+            Verdict::TooHigh => write!(f, "too high"),
+            Verdict::TooLow => write!(f, "too low"),
+            Verdict::Wrong => write!(f, "wrong"),
+            Verdict::RateLimited { wait } => write!(f, "rate limited for {}s", wait.as_secs()),
+            Verdict::AlreadySolved => write!(f, "already solved"),
+        }
+    }
+}
+
+impl FromStr for Verdict {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "correct" => Ok(Verdict::Correct),
+            "too high" => Ok(Verdict::TooHigh),
+            "too low" => Ok(Verdict::TooLow),
+            "wrong" => Ok(Verdict::Wrong),
+            "already solved" => Ok(Verdict::AlreadySolved),
+            _ => Err(format!("unknown verdict '{s}'")),
+        }
+    }
+}
+
+struct Submission {
+    year: u32,
+    day: u32,
+    part: Part,
+    answer: String,
+    verdict: Verdict,
+}
+
+// Every answer that got a final verdict, one tab separated line each:
+// `<year> <day> <part> <answer> <verdict>`
+pub struct SubmissionLog {
+    path: PathBuf,
+    submissions: Vec<Submission>,
+}
+
+impl SubmissionLog {
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, Box<dyn Error>> {
+        let path = path.into();
+        let mut submissions = Vec::new();
+        if path.exists() {
+            for line in read_to_string(&path)?.lines() {
+                let fields = line.split('\t').collect::<Vec<_>>();
+                let [year, day, part, answer, verdict] = fields[..] else {
+                    return Err(format!("malformed submission '{line}'").into());
+                };
+                submissions.push(Submission {
+                    year: year.parse()?,
+                    day: day.parse()?,
+                    part: part.parse()?,
+                    answer: answer.to_owned(),
+                    verdict: verdict.parse()?,
+                });
+            }
+        }
+        Ok(SubmissionLog { path, submissions })
+    }
+
+    pub fn find(&self, year: u32, day: u32, part: Part, answer: &str) -> Option<&Verdict> {
+        self.submissions
+            .iter()
+            .find(|s| s.year == year && s.day == day && s.part == part && s.answer == answer)
+            .map(|s| &s.verdict)
+    }
+
+    fn record(&mut self, submission: Submission) -> Result<(), Box<dyn Error>> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(
+            file,
+            "{}\t{}\t{}\t{}\t{}",
+            submission.year, submission.day, submission.part, submission.answer, submission.verdict
+        )?;
+        self.submissions.push(submission);
+        Ok(())
+    }
+}
+
+pub fn submit(year: u32, day: u32, part: Part, answer: &Answer) -> Result<Verdict, Box<dyn Error>> {
+    let log_path = input::inputs_dir()?
+        .join(input::account())
+        .join("submissions.tsv");
+    let mut log = SubmissionLog::load(log_path)?;
+    submit_with(&HttpProvider::from_env(), &mut log, year, day, part, answer)
+}
+
+// Answers already in the log are never sent again, the logged verdict is
+// returned instead
+pub fn submit_with(
+    provider: &HttpProvider,
+    log: &mut SubmissionLog,
+    year: u32,
+    day: u32,
+    part: Part,
+    answer: &Answer,
+) -> Result<Verdict, Box<dyn Error>> {
+    if *answer == Answer::Unsolved {
+        return Err(format!("day {day} part {part} is not solved").into());
+    }
+    let answer = answer.to_string();
+    if let Some(verdict) = log.find(year, day, part, &answer) {
+        return Ok(verdict.clone());
+    }
+
+    let html = provider.post_answer(year, day, part, &answer)?;
+    let verdict = Verdict::parse(&html).ok_or("could not make sense of the answer page")?;
+    if !matches!(verdict, Verdict::RateLimited { .. }) {
+        log.record(Submission {
+            year,
+            day,
+            part,
+            answer,
+            verdict: verdict.clone(),
+        })?;
+    }
+    Ok(verdict)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::input::mock::*;
+
+    use super::*;
+
+    const TOO_HIGH: &str = "<article><p>That's not the right answer; your answer is too high.  \
+        If you're stuck, make sure you're using the full input data.</p></article>";
+    const TOO_RECENT: &str = "<article><p>You gave an answer too recently; you have to wait \
+        after submitting an answer before trying again.  You have 4m 32s left to wait.</p></article>";
+
+    #[test]
+    fn parse_verdicts() {
+        assert_eq!(
+            Verdict::parse("<p>That's the right answer!  You are one gold star closer.</p>"),
+            Some(Verdict::Correct)
+        );
+        assert_eq!(Verdict::parse(TOO_HIGH), Some(Verdict::TooHigh));
+        assert_eq!(
+            Verdict::parse("That's not the right answer; your answer is too low."),
+            Some(Verdict::TooLow)
+        );
+        assert_eq!(
+            Verdict::parse("That's not the right answer.  If you're stuck, ..."),
+            Some(Verdict::Wrong)
+        );
+        assert_eq!(
+            Verdict::parse(TOO_RECENT),
+            Some(Verdict::RateLimited {
+                wait: Duration::from_secs(272)
+            })
+        );
+        assert_eq!(
+            Verdict::parse(
+                "You don't seem to be solving the right level.  Did you already complete it?"
+            ),
+            Some(Verdict::AlreadySolved)
+        );
+        assert_eq!(Verdict::parse("<html></html>"), None);
+    }
+
+    #[test]
+    fn wrong_answers_are_sent_once() {
+        let dir = temp_dir("submit");
+        let (url, server) = mock_server(&[(200, TOO_RECENT), (200, TOO_HIGH)]);
+        let provider = mock_provider(&url);
+        let mut log = SubmissionLog::load(dir.join("submissions.tsv")).unwrap();
+        let answer = Answer::from(501);
+
+        let verdict = submit_with(&provider, &mut log, 2023, 17, Part::One, &answer).unwrap();
+        assert!(matches!(verdict, Verdict::RateLimited { .. }));
+        let verdict = submit_with(&provider, &mut log, 2023, 17, Part::One, &answer).unwrap();
+        assert_eq!(verdict, Verdict::TooHigh);
+
+        let requests = server.join().unwrap();
+        assert!(requests[1].starts_with("POST /2023/day/17/answer HTTP/1.1\r\n"));
+        assert!(requests[1].ends_with("level=1&answer=501"));
+
+        // The mock is gone, so this can only be answered from the reloaded log
+        let mut log = SubmissionLog::load(dir.join("submissions.tsv")).unwrap();
+        let verdict = submit_with(&provider, &mut log, 2023, 17, Part::One, &answer).unwrap();
+        assert_eq!(verdict, Verdict::TooHigh);
+        assert!(submit_with(&provider, &mut log, 2023, 17, Part::Two, &Answer::Unsolved).is_err());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}